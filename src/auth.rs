@@ -24,10 +24,11 @@ impl<W: Write> State<W> {
                     ("courseKey", props.get_prop("courseKey")?),
                     ("projectNumber", props.get_prop("projectNumber")?),
                 ])
+                .map_err(Box::new)
                 .and_then(|resp| {
                     resp.into_reader()
                         .read_to_end(&mut submit_user)
-                        .map_err(Into::into)
+                        .map_err(|e| Box::new(e.into()))
                 })
                 .wrap_err("failed to negotiate one-time password with the server")?;
 
@@ -0,0 +1,170 @@
+use std::{
+    env::{current_dir, set_current_dir},
+    fs::File,
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use eyre::{bail, Result, WrapErr};
+
+use crate::{
+    config::Config,
+    discover::{find_submit_files, read_submit_file},
+    state::State,
+    warn, Props,
+};
+
+struct Entry {
+    dir: PathBuf,
+    props: Props,
+}
+
+impl<W: Write> State<W> {
+    /// Lists the projects with a .submit file under the working directory,
+    /// and submits the ones picked by the user, or all of them with --all
+    pub fn submit_recursive(&mut self, cfg: &Config) -> Result<()> {
+        let mut entries = Vec::new();
+        for path in find_submit_files()? {
+            // one broken project shouldn't keep the others from being submitted
+            match read_submit_file(&path)
+                .and_then(|props| self.validate_submit_file(&path, &props).map(|()| props))
+            {
+                Ok(props) => entries.push(Entry {
+                    dir: path.parent().map_or_else(PathBuf::new, Path::to_owned),
+                    props,
+                }),
+                Err(e) => warn!(self, "skipping {}: {e:#}", path.display()),
+            }
+        }
+
+        if entries.is_empty() {
+            bail!("failed to find any .submit files");
+        }
+
+        for (i, entry) in entries.iter().enumerate() {
+            self.print_entry(i + 1, entry, cfg)?;
+        }
+
+        if cfg.no_submit {
+            return Ok(());
+        }
+
+        let selected = if cfg.all {
+            (0 .. entries.len()).collect()
+        } else {
+            if !io::stdin().is_terminal() {
+                bail!("refusing to prompt for projects because stdin is not a terminal, use --all to submit all of them");
+            }
+            self.prompt("Projects to submit (e.g. 1 3, or all)")?;
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .wrap_err("failed to prompt for projects")?;
+            parse_selection(&input, entries.len())?
+        };
+        if selected.is_empty() {
+            bail!("no projects selected");
+        }
+
+        let cwd = current_dir().wrap_err("failed to get current directory")?;
+        let mut failed = 0;
+        for i in selected {
+            let entry = &entries[i];
+            writeln!(self.out, "Submitting {}", display_dir(&entry.dir).display(),)?;
+
+            set_current_dir(cwd.join(&entry.dir)).wrap_err("failed to set current directory")?;
+            let user_props = File::open(".submitUser")
+                .ok()
+                .and_then(|file| java_properties::read(file).ok())
+                .unwrap_or_default();
+            let res = self.submit(user_props, &entry.props, cfg, Path::new("."));
+            set_current_dir(&cwd).wrap_err("failed to set current directory")?;
+
+            if let Err(e) = res {
+                warn!(
+                    self,
+                    "failed to submit {}: {e:#}",
+                    display_dir(&entry.dir).display()
+                );
+                failed += 1;
+            }
+        }
+
+        if failed != 0 {
+            bail!("failed to submit {failed} project(s)");
+        }
+
+        Ok(())
+    }
+
+    fn print_entry(&mut self, n: usize, entry: &Entry, cfg: &Config) -> Result<()> {
+        let name = |key| entry.props.get(key).map_or("?", String::as_str);
+        write!(
+            self.out,
+            "{n:>3}. {} project {} ({})",
+            name("courseName"),
+            name("projectNumber"),
+            display_dir(&entry.dir).display(),
+        )?;
+
+        // the listing is still useful without the due dates, e.g. when offline
+        match self.project_due(&entry.props, cfg) {
+            Ok(Some(due)) => writeln!(self.out, ", due {due}")?,
+            Ok(None) => writeln!(self.out)?,
+            Err(e) => {
+                writeln!(self.out)?;
+                warn!(self, "failed to find the due date: {e}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn display_dir(dir: &Path) -> &Path {
+    if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    }
+}
+
+/// Parses the numbers of the projects picked by the user,
+/// returning their indices in order without duplicates
+fn parse_selection(input: &str, len: usize) -> Result<Vec<usize>> {
+    let input = input.trim();
+    if input == "all" {
+        return Ok((0 .. len).collect());
+    }
+
+    let mut selected = Vec::new();
+    for word in input.split([',', ' ']).filter(|word| !word.is_empty()) {
+        let Some(i) = word
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1 ..= len).contains(n))
+        else {
+            bail!("invalid project number {word}, expected a number from 1 to {len}");
+        };
+        if !selected.contains(&(i - 1)) {
+            selected.push(i - 1);
+        }
+    }
+
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_selection;
+
+    #[test]
+    fn selection() {
+        assert_eq!(parse_selection("all\n", 3).unwrap(), [0, 1, 2]);
+        assert_eq!(parse_selection("3, 1 3\n", 3).unwrap(), [2, 0]);
+        assert_eq!(parse_selection("\n", 3).unwrap(), [] as [usize; 0]);
+        assert!(parse_selection("0", 3).is_err());
+        assert!(parse_selection("4", 3).is_err());
+        assert!(parse_selection("foo", 3).is_err());
+    }
+}
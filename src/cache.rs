@@ -0,0 +1,354 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use eyre::{bail, Result, WrapErr};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+
+use crate::{config::Config, state::State, warn, Props, PropsExt};
+
+struct Cache {
+    calendar: PathBuf,
+    meta: PathBuf,
+}
+
+struct Cached {
+    calendar: String,
+    meta: Props,
+}
+
+impl<W: Write> State<W> {
+    /// Downloads the course calendar,
+    /// serving it from the cache when it is fresh enough or when offline
+    pub(crate) fn download_calendar(&mut self, props: &Props, cfg: &Config) -> Result<String> {
+        let url = format!(
+            "{}/feed/CourseCalendar?courseKey={}",
+            props.get_prop("baseURL")?,
+            props.get_prop("courseKey")?,
+        );
+        let cache = cache_dir().map(|dir| Cache::new(&dir, &url));
+        self.fetch_calendar(&url, cache.as_ref(), cfg)
+    }
+
+    fn fetch_calendar(&mut self, url: &str, cache: Option<&Cache>, cfg: &Config) -> Result<String> {
+        let cached = cache.and_then(Cache::load);
+
+        if let Some(cached) = &cached {
+            if cfg.offline || cached.age() < cfg.cache_ttl.whole_seconds() {
+                return Ok(cached.calendar.clone());
+            }
+        } else if cfg.offline {
+            bail!("the course calendar is not cached, try again without --offline");
+        }
+
+        let mut req = ureq::get(url);
+        if let Some(meta) = cached.as_ref().map(|cached| &cached.meta) {
+            if let Some(etag) = meta.get("etag") {
+                req = req.set("If-None-Match", etag);
+            }
+            if let Some(last_modified) = meta.get("lastModified") {
+                req = req.set("If-Modified-Since", last_modified);
+            }
+        }
+
+        let resp = match (req.call(), cached) {
+            (Ok(resp), Some(mut cached)) if resp.status() == 304 => {
+                if let Some(cache) = cache {
+                    cached.meta.insert("fetched".into(), now().to_string());
+                    self.store(cache, None, &cached.meta);
+                }
+                return Ok(cached.calendar);
+            }
+
+            (Ok(resp), _) => resp,
+
+            (Err(e @ ureq::Error::Transport(_)), Some(cached)) => {
+                warn!(
+                    self,
+                    "failed to download the course calendar, using the cached version instead: {e}",
+                );
+                return Ok(cached.calendar);
+            }
+
+            (Err(e), _) => {
+                return Err(e).wrap_err("failed to download the course calendar");
+            }
+        };
+
+        let mut meta = HashMap::new();
+        meta.insert("fetched".into(), now().to_string());
+        if let Some(etag) = resp.header("ETag") {
+            meta.insert("etag".into(), etag.into());
+        }
+        if let Some(last_modified) = resp.header("Last-Modified") {
+            meta.insert("lastModified".into(), last_modified.into());
+        }
+
+        let calendar = resp
+            .into_string()
+            .wrap_err("failed to parse the course calendar")?;
+
+        if let Some(cache) = cache {
+            self.store(cache, Some(&calendar), &meta);
+        }
+
+        Ok(calendar)
+    }
+
+    fn store(&mut self, cache: &Cache, calendar: Option<&str>, meta: &Props) {
+        if let Err(e) = cache.store(calendar, meta) {
+            warn!(self, "failed to cache the course calendar: {e:?}");
+        }
+    }
+}
+
+impl Cache {
+    /// Keys the cache by a hash of the full URL,
+    /// so different servers and courses never share a cache entry
+    fn new(dir: &Path, url: &str) -> Self {
+        let name = format!("{:x}", Sha256::digest(url));
+        Self {
+            calendar: dir.join(format!("{name}.ics")),
+            meta: dir.join(format!("{name}.properties")),
+        }
+    }
+
+    fn load(&self) -> Option<Cached> {
+        Some(Cached {
+            calendar: fs::read_to_string(&self.calendar).ok()?,
+            meta: java_properties::read(File::open(&self.meta).ok()?).ok()?,
+        })
+    }
+
+    fn store(&self, calendar: Option<&str>, meta: &Props) -> Result<()> {
+        if let Some(dir) = self.calendar.parent() {
+            fs::create_dir_all(dir).wrap_err("failed to create the cache directory")?;
+        }
+        if let Some(calendar) = calendar {
+            fs::write(&self.calendar, calendar)
+                .wrap_err_with(|| format!("failed to write to {}", self.calendar.display()))?;
+        }
+        java_properties::write(
+            File::create(&self.meta)
+                .wrap_err_with(|| format!("failed to write to {}", self.meta.display()))?,
+            meta,
+        )
+        .wrap_err_with(|| format!("failed to write to {}", self.meta.display()))
+    }
+}
+
+impl Cached {
+    /// Number of seconds since the calendar was last fetched
+    fn age(&self) -> i64 {
+        self.meta
+            .get("fetched")
+            .and_then(|fetched| fetched.parse::<i64>().ok())
+            .map_or(i64::MAX, |fetched| now() - fetched)
+    }
+}
+
+fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+#[cfg(unix)]
+fn cache_dir() -> Option<PathBuf> {
+    xdg::BaseDirectories::with_prefix("sagoin")
+        .ok()
+        .map(|dirs| dirs.get_cache_home())
+}
+
+#[cfg(windows)]
+fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("sagoin"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::mpsc::{channel, Receiver},
+        thread,
+    };
+
+    use tempfile::tempdir;
+    use time::Duration;
+
+    use super::{now, Cache};
+    use crate::{config::Config, state::State};
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n";
+
+    /// Serves the responses in order, sending the request headers back
+    fn serve(responses: &'static [&'static str]) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = channel();
+
+        thread::spawn(move || {
+            for (stream, response) in listener.incoming().zip(responses) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&mut stream);
+
+                let mut head = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+                tx.send(head).unwrap();
+
+                write!(stream, "{response}").unwrap();
+            }
+        });
+
+        (
+            format!("http://{addr}/feed/CourseCalendar?courseKey=abc"),
+            rx,
+        )
+    }
+
+    const OK: &str = "HTTP/1.1 200 OK\r\n\
+        ETag: \"v1\"\r\n\
+        Last-Modified: Mon, 16 Sep 2024 00:00:00 GMT\r\n\
+        Content-Length: 32\r\n\
+        Connection: close\r\n\r\n\
+        BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n";
+
+    const NOT_MODIFIED: &str = "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n";
+
+    /// Stores the calendar in the cache as if it was fetched the given number of seconds ago
+    fn store(cache: &Cache, age: i64) {
+        let meta = HashMap::from([("fetched".into(), (now() - age).to_string())]);
+        cache.store(Some(CALENDAR), &meta).unwrap();
+    }
+
+    #[test]
+    fn ttl() {
+        let dir = tempdir().unwrap();
+        let (url, rx) = serve(&[OK, OK]);
+        let cache = Cache::new(dir.path(), &url);
+        let cfg = Config {
+            cache_ttl: Duration::HOUR,
+            ..Default::default()
+        };
+
+        let mut state = State::sink();
+        assert_eq!(
+            state.fetch_calendar(&url, Some(&cache), &cfg).unwrap(),
+            CALENDAR
+        );
+        assert_eq!(
+            state.fetch_calendar(&url, Some(&cache), &cfg).unwrap(),
+            CALENDAR
+        );
+        rx.recv().unwrap();
+        assert!(rx.try_recv().is_err());
+
+        store(&cache, 2 * 60 * 60);
+        assert_eq!(
+            state.fetch_calendar(&url, Some(&cache), &cfg).unwrap(),
+            CALENDAR
+        );
+        rx.recv().unwrap();
+    }
+
+    #[test]
+    fn revalidate() {
+        let dir = tempdir().unwrap();
+        let (url, rx) = serve(&[OK, NOT_MODIFIED]);
+        let cache = Cache::new(dir.path(), &url);
+        let cfg = Config::default();
+
+        let mut state = State::sink();
+        assert_eq!(
+            state.fetch_calendar(&url, Some(&cache), &cfg).unwrap(),
+            CALENDAR
+        );
+        let head = rx.recv().unwrap();
+        assert!(!head.contains("If-None-Match"), "{head}");
+
+        assert_eq!(
+            state.fetch_calendar(&url, Some(&cache), &cfg).unwrap(),
+            CALENDAR
+        );
+        let head = rx.recv().unwrap().to_lowercase();
+        assert!(head.contains("if-none-match: \"v1\""), "{head}");
+        assert!(
+            head.contains("if-modified-since: mon, 16 sep 2024 00:00:00 gmt"),
+            "{head}",
+        );
+        assert!(cache.load().unwrap().age() < 60);
+    }
+
+    #[test]
+    fn offline() {
+        let dir = tempdir().unwrap();
+        let (url, rx) = serve(&[]);
+        let cache = Cache::new(dir.path(), &url);
+        let cfg = Config {
+            offline: true,
+            ..Default::default()
+        };
+
+        let mut state = State::sink();
+        assert!(state.fetch_calendar(&url, Some(&cache), &cfg).is_err());
+
+        store(&cache, 365 * 24 * 60 * 60);
+        assert_eq!(
+            state.fetch_calendar(&url, Some(&cache), &cfg).unwrap(),
+            CALENDAR
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn fallback() {
+        let dir = tempdir().unwrap();
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/feed", listener.local_addr().unwrap())
+        };
+        let cache = Cache::new(dir.path(), &url);
+
+        let mut state = State::buffer();
+        assert!(state
+            .fetch_calendar(&url, Some(&cache), &Config::default())
+            .is_err());
+
+        store(&cache, 60 * 60);
+        assert_eq!(
+            state
+                .fetch_calendar(&url, Some(&cache), &Config::default())
+                .unwrap(),
+            CALENDAR,
+        );
+        assert!(String::from_utf8(state.out)
+            .unwrap()
+            .contains("using the cached version instead"));
+    }
+
+    #[test]
+    fn key() {
+        let dir = tempdir().unwrap();
+        let x = Cache::new(
+            dir.path(),
+            "https://a.edu/feed/CourseCalendar?courseKey=a_b",
+        );
+        let y = Cache::new(
+            dir.path(),
+            "https://a.edu/feed/CourseCalendar?courseKey=a-b",
+        );
+        assert_ne!(x.calendar, y.calendar);
+        assert_ne!(x.meta, y.meta);
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    fs::File,
+    io::{Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use eyre::{Result, WrapErr};
+use globset::{Glob, GlobSetBuilder};
+
+use crate::{archive::walk, config::Config, state::State, warn, Props};
+
+/// Files larger than this are considered data rather than source code
+const LARGE: u64 = 1 << 20;
+
+/// Directories that usually only contain build outputs or dependencies
+const BUILD_DIRS: [&str; 5] = [
+    "node_modules",
+    "target",
+    "__pycache__",
+    ".gradle",
+    ".mypy_cache",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Class {
+    Source,
+    Executable,
+    Object,
+    Archive,
+    Binary,
+    Large,
+    BuildOutput,
+}
+
+impl Class {
+    fn expected(self) -> bool {
+        self == Self::Source
+    }
+}
+
+impl Display for Class {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Source => "source",
+            Self::Executable => "executable",
+            Self::Object => "object file",
+            Self::Archive => "archive",
+            Self::Binary => "binary data",
+            Self::Large => "large data",
+            Self::BuildOutput => "build output",
+        })
+    }
+}
+
+impl<W: Write> State<W> {
+    /// Warns about files that are usually not meant to be submitted,
+    /// unless they are allowed for the course
+    pub fn check_binaries(&mut self, root: &Path, props: &Props, cfg: &Config) -> Result<()> {
+        let unexpected = unexpected_files(root, props, cfg)?;
+        if unexpected.is_empty() {
+            return Ok(());
+        }
+
+        warn!(
+            self,
+            "found files that are usually not meant to be submitted:"
+        );
+        for (path, class) in unexpected {
+            writeln!(self.out, "  {}: {class}", path.display())?;
+        }
+        if let Some(course) = props.get("courseName") {
+            writeln!(
+                self.out,
+                "Add them to allowed_binaries under [courses.\"{course}\"] in the config file to allow them",
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn unexpected_files(root: &Path, props: &Props, cfg: &Config) -> Result<Vec<(PathBuf, Class)>> {
+    let mut allowed = GlobSetBuilder::new();
+    for pattern in cfg
+        .course(props)
+        .map_or(&[][..], |course| &course.allowed_binaries)
+    {
+        allowed.add(Glob::new(pattern).wrap_err_with(|| format!("invalid pattern {pattern}"))?);
+    }
+    let allowed = allowed
+        .build()
+        .wrap_err("failed to build the allowed binaries")?;
+
+    let mut unexpected = Vec::new();
+    walk(root, cfg, |path, link| {
+        if link.is_some() || allowed.is_match(path) {
+            return Ok(());
+        }
+
+        let (class, _) = classify(root, path)?;
+        if !class.expected() {
+            unexpected.push((path.to_owned(), class));
+        }
+
+        Ok(())
+    })?;
+
+    Ok(unexpected)
+}
+
+/// Classifies the file by its location, name, contents, and size, also returning its size
+pub(crate) fn classify(root: &Path, path: &Path) -> Result<(Class, u64)> {
+    let full = root.join(path);
+    let file = File::open(&full).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+    let size = file
+        .metadata()
+        .wrap_err_with(|| format!("failed to read metadata of {}", path.display()))?
+        .len();
+
+    let mut head = Vec::new();
+    file.take(8192)
+        .read_to_end(&mut head)
+        .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+
+    Ok((classify_contents(path, &head, size), size))
+}
+
+fn classify_contents(path: &Path, head: &[u8], size: u64) -> Class {
+    if path.components().any(|component| {
+        matches!(component, Component::Normal(name) if BUILD_DIRS.iter().any(|dir| name == *dir))
+    }) {
+        return Class::BuildOutput;
+    }
+
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    let ext = ext.as_deref();
+
+    if head.starts_with(b"\x7fELF") {
+        // e_type is ET_REL for relocatable object files
+        return if head.get(16) == Some(&1) {
+            Class::Object
+        } else {
+            Class::Executable
+        };
+    }
+    if head.starts_with(b"\xca\xfe\xba\xbe") {
+        // java class files and universal mach-o binaries share the same magic number
+        return if ext == Some("class") {
+            Class::Object
+        } else {
+            Class::Executable
+        };
+    }
+    if head.starts_with(b"MZ")
+        || [
+            b"\xfe\xed\xfa\xce",
+            b"\xfe\xed\xfa\xcf",
+            b"\xce\xfa\xed\xfe",
+            b"\xcf\xfa\xed\xfe",
+        ]
+        .iter()
+        .any(|magic| head.starts_with(*magic))
+        || path.file_name().is_some_and(|name| name == "a.out")
+        || matches!(ext, Some("exe" | "dll" | "so" | "dylib"))
+    {
+        return Class::Executable;
+    }
+    if head.starts_with(b"!<arch>\n")
+        || matches!(ext, Some("o" | "obj" | "class" | "pyc" | "a" | "lib"))
+    {
+        return Class::Object;
+    }
+    if [
+        &b"PK\x03\x04"[..],
+        b"\x1f\x8b",
+        b"BZh",
+        b"\xfd7zXZ\0",
+        b"7z\xbc\xaf\x27\x1c",
+        b"Rar!",
+    ]
+    .iter()
+    .any(|magic| head.starts_with(magic))
+        || head.get(257 .. 262) == Some(b"ustar")
+    {
+        return Class::Archive;
+    }
+    if size > LARGE {
+        return Class::Large;
+    }
+    if head.contains(&0) {
+        return Class::Binary;
+    }
+
+    Class::Source
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs, path::Path};
+
+    use tempfile::tempdir;
+
+    use super::{classify_contents, unexpected_files, Class};
+    use crate::config::{Config, CourseConfig};
+
+    #[test]
+    fn classes() {
+        let class = |path, head: &[u8]| classify_contents(Path::new(path), head, head.len() as u64);
+
+        assert_eq!(class("src/Main.java", b"class Main {}"), Class::Source);
+        assert_eq!(class("README", b""), Class::Source);
+        assert_eq!(
+            class("a.out", b"\x7fELF\x02\x01\x01\0\0\0\0\0\0\0\0\0\x02\0"),
+            Class::Executable
+        );
+        assert_eq!(
+            class("main.o", b"\x7fELF\x02\x01\x01\0\0\0\0\0\0\0\0\0\x01\0"),
+            Class::Object
+        );
+        assert_eq!(
+            class("bin/Main.class", b"\xca\xfe\xba\xbe\0\0\0\x41"),
+            Class::Object
+        );
+        assert_eq!(class("main.exe", b"MZ"), Class::Executable);
+        assert_eq!(class("lib/junit.jar", b"PK\x03\x04"), Class::Archive);
+        assert_eq!(class("data.bin", b"\x01\0\x02"), Class::Binary);
+        assert_eq!(
+            class("target/debug/main.d", b"main: src/main.rs"),
+            Class::BuildOutput
+        );
+        assert_eq!(
+            class("web/node_modules/x/index.js", b""),
+            Class::BuildOutput
+        );
+        assert_eq!(
+            classify_contents(Path::new("words.txt"), b"words", 2 << 20),
+            Class::Large,
+        );
+    }
+
+    #[test]
+    fn allowed() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("lib")).unwrap();
+        fs::write(dir.path().join("Main.java"), "class Main {}").unwrap();
+        fs::write(dir.path().join("Main.class"), b"\xca\xfe\xba\xbe").unwrap();
+        fs::write(dir.path().join("lib/junit.jar"), b"PK\x03\x04").unwrap();
+
+        let props = [("courseName".into(), "CMSC132".into())].into();
+        let cfg = Config {
+            courses: HashMap::from([(
+                "CMSC132".into(),
+                CourseConfig {
+                    allowed_binaries: vec!["lib/*.jar".into()],
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+
+        let unexpected = unexpected_files(dir.path(), &props, &cfg).unwrap();
+        assert_eq!(
+            unexpected,
+            [(Path::new("Main.class").into(), Class::Object)]
+        );
+
+        let unexpected = unexpected_files(dir.path(), &props, &Config::default()).unwrap();
+        assert_eq!(unexpected.len(), 2);
+    }
+}
@@ -12,21 +12,233 @@ pub(crate) struct Opts {
     #[arg(value_name = "DIRECTORY")]
     pub dir: Option<PathBuf>,
 
+    /// Specify the path to the .submit file instead of searching for it,
+    /// the files under its directory will be submitted
+    #[arg(
+        long,
+        env = "SAGOIN_SUBMIT_FILE",
+        value_name = "FILE",
+        conflicts_with = "recursive"
+    )]
+    pub submit_file: Option<PathBuf>,
+
+    /// Specify where to stop searching for the .submit file, defaults to root when unspecified
+    ///
+    /// git: the root of the git repository
+    /// home: the home directory
+    /// root: the root of the file system
+    #[arg(
+        long,
+        env = "SAGOIN_STOP_AT",
+        value_name = "BOUNDARY",
+        verbatim_doc_comment
+    )]
+    pub stop_at: Option<StopAt>,
+
+    /// Specify how many parent directories to search for the .submit file,
+    /// 0 only searches the working directory
+    #[arg(long, env = "SAGOIN_MAX_DEPTH", value_name = "DEPTH")]
+    pub max_depth: Option<usize>,
+
+    /// Use the .submit file found in a parent directory without asking for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+
     /// Don't submit the project
     #[arg(short, long)]
     pub no_submit: bool,
 
+    /// Submit the project even if nothing has changed since the last submission
+    #[arg(long)]
+    pub force: bool,
+
+    /// Submit the files tracked at the git revision instead of the working tree,
+    /// e.g. a tag or a commit hash
+    #[arg(long, value_name = "REV", conflicts_with_all = ["no_submit", "list_files", "watch"])]
+    pub rev: Option<String>,
+
+    /// Submit a prebuilt zip file instead of the files under the working directory,
+    /// gzipped tarballs ending with .tar.gz or .tgz are converted to zip files
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["no_submit", "list_files", "watch", "rev"],
+    )]
+    pub archive: Option<PathBuf>,
+
     /// List files without submitting them
     #[arg(short, long)]
     pub list_files: bool,
 
+    /// Show the classification, executable bit, and size of each file,
+    /// ignored without the --list-files flag
+    #[arg(long)]
+    pub annotate: bool,
+
+    /// Show the files as a tree, ignored without the --list-files flag
+    #[arg(long)]
+    pub tree: bool,
+
+    /// Show the number of files and their total size, ignored without the --list-files flag
+    #[arg(long)]
+    pub totals: bool,
+
+    /// Also show the size of the submission zip, which requires compressing every file,
+    /// ignored without the --totals flag
+    #[arg(long)]
+    pub compressed_size: bool,
+
+    /// Also show the files that would not be submitted and why,
+    /// ignored without the --list-files flag
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Show the properties in the .submit file, check them for problems, and exit
+    #[arg(long, conflicts_with_all = ["list_files", "recursive", "watch"])]
+    pub show_submit: bool,
+
     /// Show information about the project and exit
-    #[arg(short, long, conflicts_with = "list_files")]
+    #[arg(short, long, conflicts_with_all = ["list_files", "show_submit"])]
     pub info: bool,
 
-    /// Open the project page in a web browser
+    /// List all projects of the course and exit
+    #[arg(short = 'a', long, conflicts_with_all = ["list_files", "info", "show_submit"])]
+    pub projects: bool,
+
+    /// Only list projects that are not due yet, ignored without the --projects flag
+    #[arg(long, conflicts_with = "past")]
+    pub upcoming: bool,
+
+    /// Only list projects that are already due, ignored without the --projects flag
+    #[arg(long)]
+    pub past: bool,
+
+    /// Export the due dates of the projects to an iCalendar file and exit,
+    /// use - to write to stdout
+    #[arg(short, long, value_name = "FILE", conflicts_with_all = ["list_files", "info", "projects", "show_submit"])]
+    pub export_calendar: Option<PathBuf>,
+
+    /// Find every .submit file under the working directory,
+    /// export the projects of every course with the --export-calendar flag,
+    /// or list the projects and pick the ones to submit otherwise
+    #[arg(short, long, conflicts_with_all = ["list_files", "info", "projects", "watch", "archive"])]
+    pub recursive: bool,
+
+    /// Submit every project found without prompting, ignored without the --recursive flag
+    #[arg(long)]
+    pub all: bool,
+
+    /// Add a reminder to each exported project, e.g. 1d or 2h30m,
+    /// can be specified multiple times
+    #[arg(long = "reminder", value_name = "DURATION")]
+    pub reminders: Vec<String>,
+
+    /// Use the cached course calendar without connecting to the submit server,
+    /// only affects commands that need the course calendar
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Specify how long the cached course calendar is used without checking for updates,
+    /// e.g. 30m or 1h, defaults to 15m when unspecified
+    #[arg(long, env = "SAGOIN_CACHE_TTL", value_name = "DURATION")]
+    pub cache_ttl: Option<String>,
+
+    /// Re-submit the project whenever the files that would be submitted change,
+    /// skipping submissions identical to the previous one
+    #[arg(
+        short,
+        long,
+        conflicts_with_all = ["no_submit", "list_files", "info", "projects", "export_calendar"],
+    )]
+    pub watch: bool,
+
+    /// Specify how long the files need to stay unchanged before re-submitting, e.g. 5s,
+    /// ignored without the --watch flag, defaults to 2s when unspecified
+    #[arg(long, env = "SAGOIN_DEBOUNCE", value_name = "DURATION")]
+    pub debounce: Option<String>,
+
+    /// Specify the minimum time between submissions, e.g. 30s or 5m,
+    /// ignored without the --watch flag, defaults to 1m when unspecified
+    #[arg(long, env = "SAGOIN_WATCH_INTERVAL", value_name = "DURATION")]
+    pub watch_interval: Option<String>,
+
+    /// Specify how to compress the files in the submission zip,
+    /// defaults to deflated when unspecified
+    #[arg(long, env = "SAGOIN_COMPRESSION", value_name = "METHOD")]
+    pub compression: Option<Compression>,
+
+    /// Specify the compression level from 0 to 9, higher is smaller but slower,
+    /// 0 stores the files without compression,
+    /// ignored unless the compression method is deflated, defaults to 6 when unspecified
+    #[arg(
+        long,
+        env = "SAGOIN_COMPRESSION_LEVEL",
+        value_name = "LEVEL",
+        value_parser = clap::value_parser!(u8).range(0 ..= 9),
+    )]
+    pub compression_level: Option<u8>,
+
+    /// Write the submission zip to a temporary file instead of keeping it in memory,
+    /// useful for projects with large files
+    #[arg(long)]
+    pub spool: bool,
+
+    /// Specify how to handle symlinks, defaults to follow when unspecified
+    ///
+    /// follow: submit the files the symlinks point to inside of the project, skipping symlinks to directories and excluded files
+    /// store: submit the symlinks themselves
+    /// skip: skip the symlinks with a warning
+    #[arg(
+        long,
+        env = "SAGOIN_SYMLINKS",
+        value_name = "POLICY",
+        verbatim_doc_comment
+    )]
+    pub symlinks: Option<Symlinks>,
+
+    /// Also treat files matching the glob pattern as secrets, e.g. "data/*.csv",
+    /// can be specified multiple times
+    #[arg(long = "secret-pattern", value_name = "GLOB")]
+    pub secret_patterns: Vec<String>,
+
+    /// Submit the project even if some files look like they contain secrets,
+    /// such as private keys, tokens, and .env files
+    #[arg(long)]
+    pub allow_secrets: bool,
+
+    /// Show the current commit and warn about uncommitted changes
+    /// and untracked files that would be submitted
+    #[arg(long)]
+    pub check_git: bool,
+
+    /// Send the current commit to the submit server as an additional field with the key,
+    /// implies --check-git
+    #[arg(long, env = "SAGOIN_COMMIT_FIELD", value_name = "KEY")]
+    pub commit_field: Option<String>,
+
+    /// Don't show the upload progress, which is only shown when stderr is a terminal
     #[arg(short, long)]
-    pub open: bool,
+    pub quiet: bool,
+
+    /// Open a page on the submit server in a web browser, defaults to the project page
+    ///
+    /// project: the project page from the course calendar
+    /// server: the submit server itself
+    #[arg(
+        short,
+        long,
+        value_name = "TARGET",
+        num_args = 0 ..= 1,
+        require_equals = true,
+        default_missing_value = "project",
+        verbatim_doc_comment
+    )]
+    pub open: Option<OpenTarget>,
+
+    /// Print the URL instead of opening it in a web browser,
+    /// useful over SSH where no web browser is available
+    #[arg(long, requires = "open")]
+    pub print_url: bool,
 
     // waiting for the following issues to make the type more ergonomic
     // - https://github.com/clap-rs/clap/issues/1682
@@ -52,13 +264,24 @@ pub(crate) struct Opts {
     #[arg(short, long, env = "SAGOIN_CONFIG", value_name = "FILE")]
     pub config: Option<PathBuf>,
 
-    /// Specify how to format the due date, ignored without the --info flag,
+    /// Specify how to format the due dates shown by --info, --projects, and --recursive,
     /// defaults to "[month repr:short] [day padding:none], [hour]:[minute]" when unspecified
     ///
     /// See https://time-rs.github.io/book/api/format-description.html for more information
     #[arg(short, long, env = "SAGOIN_TIME_FORMAT", value_name = "FORMAT")]
     pub time_format: Option<String>,
 
+    /// Specify the time zone to show the due dates in with --info, --projects, and --recursive,
+    /// and to read due dates without a time zone in, e.g. for --export-calendar,
+    /// accepts IANA time zone names like America/New_York,
+    /// defaults to the local time zone when unspecified
+    #[arg(short = 'z', long, env = "SAGOIN_TIME_ZONE", value_name = "TIME_ZONE")]
+    pub time_zone: Option<String>,
+
+    /// Also show how far away the due dates shown by --info, --projects, and --recursive are
+    #[arg(long)]
+    pub relative_time: bool,
+
     /// Specify the username for authentication,
     /// see --username-type for more information
     #[arg(short, long, env = "SAGOIN_USERNAME")]
@@ -147,3 +370,47 @@ pub(crate) enum InputType {
     File,
     Text,
 }
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+#[cfg_attr(
+    not_build,
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub(crate) enum Compression {
+    Stored,
+    #[default]
+    Deflated,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[cfg_attr(
+    not_build,
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub(crate) enum Symlinks {
+    #[default]
+    Follow,
+    Store,
+    Skip,
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+#[cfg_attr(
+    not_build,
+    derive(serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub(crate) enum StopAt {
+    Git,
+    Home,
+    #[default]
+    Root,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OpenTarget {
+    Project,
+    Server,
+}
@@ -1,34 +1,91 @@
 use std::{
+    collections::HashMap,
     ffi::OsString,
     fs,
     io::{StderrLock, Write},
-    path::PathBuf,
+    path::{self, Path, PathBuf},
 };
 
 use clap::Parser;
-use eyre::{Result, WrapErr};
+use eyre::{bail, eyre, Result, WrapErr};
 use serde::Deserialize;
+use time::Duration;
 
 use crate::{
-    cli::{InputType, Opts},
+    cli::{Compression, InputType, OpenTarget, Opts, StopAt, Symlinks},
     state::State,
-    warn,
+    warn, Props,
 };
 
+#[cfg_attr(test, derive(Default))]
 pub struct Config {
     pub dir: Option<PathBuf>,
+    pub(crate) submit_file: Option<PathBuf>,
+    pub(crate) stop_at: StopAt,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) yes: bool,
     pub no_submit: bool,
+    pub(crate) force: bool,
+    pub(crate) rev: Option<String>,
+    pub(crate) archive: Option<PathBuf>,
     pub list_files: bool,
+    pub show_submit: bool,
     pub info: bool,
-    pub open: bool,
+    pub projects: bool,
+    pub upcoming: bool,
+    pub past: bool,
+    pub export_calendar: Option<PathBuf>,
+    pub recursive: bool,
+    pub(crate) all: bool,
+    pub(crate) reminders: Vec<Duration>,
+    pub(crate) offline: bool,
+    pub(crate) cache_ttl: Duration,
+    pub watch: bool,
+    pub(crate) debounce: Duration,
+    pub(crate) watch_interval: Duration,
+    pub(crate) compression: Compression,
+    pub(crate) compression_level: Option<u8>,
+    pub(crate) spool: bool,
+    pub(crate) symlinks: Symlinks,
+    pub(crate) secret_patterns: Vec<String>,
+    pub(crate) allow_secrets: bool,
+    pub(crate) check_git: bool,
+    pub(crate) commit_field: Option<String>,
+    pub(crate) quiet: bool,
+    pub annotate: bool,
+    pub tree: bool,
+    pub totals: bool,
+    pub compressed_size: bool,
+    pub verbose: bool,
+    pub open: Option<OpenTarget>,
+    pub(crate) print_url: bool,
     pub fields: Vec<String>,
     pub time_format: String,
+    pub time_zone: Option<String>,
+    pub relative_time: bool,
     pub(crate) username: Option<Credential>,
     pub(crate) password: Option<Credential>,
     pub(crate) pre_submit_hook: Option<OsString>,
     pub(crate) post_submit_hook: Option<OsString>,
     pub(crate) client_name: String,
     pub(crate) client_version: String,
+    pub(crate) courses: HashMap<String, CourseConfig>,
+}
+
+/// Settings that only apply to a specific course, keyed by the course name in .submit
+#[derive(Default, Deserialize)]
+pub(crate) struct CourseConfig {
+    #[serde(default)]
+    pub(crate) allowed_binaries: Vec<String>,
+    #[serde(default)]
+    pub(crate) required_files: Vec<String>,
+}
+
+impl Config {
+    /// Settings for the course of the project
+    pub(crate) fn course(&self, props: &Props) -> Option<&CourseConfig> {
+        self.courses.get(props.get("courseName")?)
+    }
 }
 
 pub(crate) enum Credential {
@@ -39,7 +96,26 @@ pub(crate) enum Credential {
 
 #[derive(Deserialize)]
 struct ConfigFile {
+    stop_at: Option<StopAt>,
+    max_depth: Option<usize>,
     time_format: Option<String>,
+    time_zone: Option<String>,
+    relative_time: Option<bool>,
+    #[serde(default)]
+    reminders: Vec<String>,
+    cache_ttl: Option<String>,
+    print_url: Option<bool>,
+    debounce: Option<String>,
+    watch_interval: Option<String>,
+    compression: Option<Compression>,
+    compression_level: Option<u8>,
+    spool: Option<bool>,
+    symlinks: Option<Symlinks>,
+    #[serde(default)]
+    secret_patterns: Vec<String>,
+    check_git: Option<bool>,
+    commit_field: Option<String>,
+    quiet: Option<bool>,
     username: Option<String>,
     username_type: Option<InputType>,
     password: Option<String>,
@@ -48,6 +124,8 @@ struct ConfigFile {
     post_submit_hook: Option<OsString>,
     client_name: Option<String>,
     client_version: Option<String>,
+    #[serde(default)]
+    courses: HashMap<String, CourseConfig>,
 }
 
 pub fn load_config() -> Result<(Config, State<StderrLock<'static>>)> {
@@ -65,15 +143,78 @@ pub fn load_config() -> Result<(Config, State<StderrLock<'static>>)> {
 
             Config {
                 dir: opts.dir,
+                submit_file: opts.submit_file.map(absolute).transpose()?,
+                stop_at: opts.stop_at.or(cfg.stop_at).unwrap_or_default(),
+                max_depth: opts.max_depth.or(cfg.max_depth),
+                yes: opts.yes,
                 no_submit: opts.no_submit,
+                force: opts.force,
+                rev: opts.rev,
+                archive: opts.archive.map(absolute).transpose()?,
                 list_files: opts.list_files,
+                show_submit: opts.show_submit,
                 info: opts.info,
+                projects: opts.projects,
+                upcoming: opts.upcoming,
+                past: opts.past,
+                export_calendar: opts.export_calendar.map(absolute_output).transpose()?,
+                recursive: opts.recursive,
+                all: opts.all,
+                reminders: parse_durations(if opts.reminders.is_empty() {
+                    cfg.reminders
+                } else {
+                    opts.reminders
+                })?,
+                offline: opts.offline,
+                cache_ttl: opts
+                    .cache_ttl
+                    .or(cfg.cache_ttl)
+                    .map_or(Ok(Duration::minutes(15)), |ttl| parse_duration(&ttl))?,
+                watch: opts.watch,
+                debounce: opts
+                    .debounce
+                    .or(cfg.debounce)
+                    .map_or(Ok(Duration::seconds(2)), |debounce| {
+                        parse_duration(&debounce)
+                    })?,
+                watch_interval: opts
+                    .watch_interval
+                    .or(cfg.watch_interval)
+                    .map_or(Ok(Duration::minutes(1)), |interval| {
+                        parse_duration(&interval)
+                    })?,
+                compression: opts.compression.or(cfg.compression).unwrap_or_default(),
+                compression_level: match opts.compression_level.or(cfg.compression_level) {
+                    Some(level @ 10 ..) => {
+                        bail!("invalid compression level {level}, expected a number from 0 to 9");
+                    }
+                    level => level,
+                },
+                spool: opts.spool || cfg.spool.unwrap_or(false),
+                symlinks: opts.symlinks.or(cfg.symlinks).unwrap_or_default(),
+                secret_patterns: cfg
+                    .secret_patterns
+                    .into_iter()
+                    .chain(opts.secret_patterns)
+                    .collect(),
+                allow_secrets: opts.allow_secrets,
+                check_git: opts.check_git || cfg.check_git.unwrap_or(false),
+                commit_field: opts.commit_field.or(cfg.commit_field),
+                quiet: opts.quiet || cfg.quiet.unwrap_or(false),
+                annotate: opts.annotate,
+                tree: opts.tree,
+                totals: opts.totals,
+                compressed_size: opts.compressed_size,
+                verbose: opts.verbose,
                 open: opts.open,
+                print_url: opts.print_url || cfg.print_url.unwrap_or(false),
                 fields: opts.fields,
                 time_format: opts
                     .time_format
                     .or(cfg.time_format)
                     .unwrap_or_else(default_time_format),
+                time_zone: opts.time_zone.or(cfg.time_zone),
+                relative_time: opts.relative_time || cfg.relative_time.unwrap_or(false),
                 username: Credential::from_fallback(
                     &mut state,
                     "username",
@@ -98,16 +239,62 @@ pub fn load_config() -> Result<(Config, State<StderrLock<'static>>)> {
                     .client_version
                     .or(cfg.client_version)
                     .unwrap_or_else(|| env!("CARGO_PKG_VERSION").into()),
+                courses: cfg.courses,
             }
         } else {
             Config {
                 dir: opts.dir,
+                submit_file: opts.submit_file.map(absolute).transpose()?,
+                stop_at: opts.stop_at.unwrap_or_default(),
+                max_depth: opts.max_depth,
+                yes: opts.yes,
                 no_submit: opts.no_submit,
+                force: opts.force,
+                rev: opts.rev,
+                archive: opts.archive.map(absolute).transpose()?,
                 list_files: opts.list_files,
+                show_submit: opts.show_submit,
                 info: opts.info,
+                projects: opts.projects,
+                upcoming: opts.upcoming,
+                past: opts.past,
+                export_calendar: opts.export_calendar.map(absolute_output).transpose()?,
+                recursive: opts.recursive,
+                all: opts.all,
+                reminders: parse_durations(opts.reminders)?,
+                offline: opts.offline,
+                cache_ttl: opts
+                    .cache_ttl
+                    .map_or(Ok(Duration::minutes(15)), |ttl| parse_duration(&ttl))?,
+                watch: opts.watch,
+                debounce: opts.debounce.map_or(Ok(Duration::seconds(2)), |debounce| {
+                    parse_duration(&debounce)
+                })?,
+                watch_interval: opts
+                    .watch_interval
+                    .map_or(Ok(Duration::minutes(1)), |interval| {
+                        parse_duration(&interval)
+                    })?,
+                compression: opts.compression.unwrap_or_default(),
+                compression_level: opts.compression_level,
+                spool: opts.spool,
+                symlinks: opts.symlinks.unwrap_or_default(),
+                secret_patterns: opts.secret_patterns,
+                allow_secrets: opts.allow_secrets,
+                check_git: opts.check_git,
+                commit_field: opts.commit_field,
+                quiet: opts.quiet,
+                annotate: opts.annotate,
+                tree: opts.tree,
+                totals: opts.totals,
+                compressed_size: opts.compressed_size,
+                verbose: opts.verbose,
                 open: opts.open,
+                print_url: opts.print_url,
                 fields: opts.fields,
                 time_format: opts.time_format.unwrap_or_else(default_time_format),
+                time_zone: opts.time_zone,
+                relative_time: opts.relative_time,
                 username: opts.username.and_then(|user| {
                     Credential::from_os_string(&mut state, "username", user, opts.username_type)
                 }),
@@ -120,6 +307,7 @@ pub fn load_config() -> Result<(Config, State<StderrLock<'static>>)> {
                 client_version: opts
                     .client_version
                     .unwrap_or_else(|| env!("CARGO_PKG_VERSION").into()),
+                courses: HashMap::new(),
             }
         },
         state,
@@ -130,6 +318,59 @@ fn default_time_format() -> String {
     "[month repr:short] [day padding:none], [hour]:[minute]".into()
 }
 
+/// Resolves the path before the working directory changes
+fn absolute(path: PathBuf) -> Result<PathBuf> {
+    path::absolute(&path).wrap_err_with(|| format!("failed to resolve {}", path.display()))
+}
+
+/// Resolves the output path before the working directory changes, keeping - for stdout
+fn absolute_output(path: PathBuf) -> Result<PathBuf> {
+    if path == Path::new("-") {
+        Ok(path)
+    } else {
+        absolute(path)
+    }
+}
+
+fn parse_durations(xs: Vec<String>) -> Result<Vec<Duration>> {
+    xs.iter().map(|x| parse_duration(x)).collect()
+}
+
+/// Parses durations like 1d, 2h30m, 1w2d, and 0s
+fn parse_duration(input: &str) -> Result<Duration> {
+    let mut total = Duration::ZERO;
+    let mut n = String::new();
+
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            n.push(c);
+            continue;
+        }
+
+        let x: i64 = n.parse().map_err(|_| eyre!("invalid duration: {input}"))?;
+        n.clear();
+
+        let unit = match c {
+            'w' => Duration::WEEK,
+            'd' => Duration::DAY,
+            'h' => Duration::HOUR,
+            'm' => Duration::MINUTE,
+            's' => Duration::SECOND,
+            _ => bail!("invalid unit {c:?} in duration: {input}"),
+        };
+        total = x
+            .checked_mul(unit.whole_seconds())
+            .and_then(|secs| total.checked_add(Duration::seconds(secs)))
+            .ok_or_else(|| eyre!("invalid duration: {input}"))?;
+    }
+
+    if n.is_empty() && !input.is_empty() {
+        Ok(total)
+    } else {
+        Err(eyre!("invalid duration: {input}"))
+    }
+}
+
 impl Credential {
     fn from_fallback(
         state: &mut State<impl Write>,
@@ -193,7 +434,9 @@ fn find_config_file() -> Option<PathBuf> {
 mod tests {
     use std::ffi::OsString;
 
-    use super::Credential;
+    use time::Duration;
+
+    use super::{parse_duration, Credential};
     use crate::{cli::InputType, state::State};
 
     #[test]
@@ -268,4 +511,25 @@ mod tests {
         ));
         assert!(state.out.is_empty());
     }
+
+    #[test]
+    fn duration() {
+        assert_eq!(parse_duration("1d").unwrap(), Duration::days(1));
+        assert_eq!(
+            parse_duration("2h30m").unwrap(),
+            Duration::hours(2) + Duration::minutes(30),
+        );
+        assert_eq!(
+            parse_duration("1w2d").unwrap(),
+            Duration::weeks(1) + Duration::days(2),
+        );
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("1").is_err());
+        assert!(parse_duration("h").is_err());
+        assert_eq!(parse_duration("0s").unwrap(), Duration::ZERO);
+        assert!(parse_duration("1x").is_err());
+        assert!(parse_duration("99999999999999w").is_err());
+        assert!(parse_duration("99999999999999999999s").is_err());
+        assert!(parse_duration("9223372036854775807s1s").is_err());
+    }
 }
@@ -1,153 +1,734 @@
 use std::io::{stdout, Write};
 
-use eyre::{eyre, Report, Result, WrapErr};
-use icalendar::parser::{read_calendar_simple, unfold, Component};
-use time::{format_description, macros::format_description, PrimitiveDateTime};
+use eyre::{bail, eyre, Result, WrapErr};
+use icalendar::parser::{read_calendar_simple, unfold, Component, Property};
+use time::{
+    format_description, macros::format_description, Date, Duration, OffsetDateTime,
+    PrimitiveDateTime, Time, UtcOffset,
+};
+use time_tz::{timezones, OffsetDateTimeExt, PrimitiveDateTimeExt, TimeZone, Tz};
 
-use crate::{Props, PropsExt};
+use crate::{
+    config::Config,
+    state::State,
+    text::{hide_backslashes, html_to_text, restore_backslashes, unescape},
+    warn, Props, PropsExt,
+};
 
-#[derive(Default)]
-pub struct CourseInfo {
-    summary: String,
-    due: Option<String>,
-    description: Option<String>,
-    url: Option<String>,
+pub(crate) struct Event {
+    pub(crate) summary: String,
+    pub(crate) due: Option<Due>,
+    pub(crate) description: Option<String>,
+    pub(crate) url: Option<String>,
+    pub(crate) uid: Option<String>,
 }
 
-pub fn print_course_info(props: &Props, fmt: String) -> Result<()> {
-    let info = get_course_info(props)?;
-    let mut out = stdout().lock();
+pub(crate) struct Project {
+    pub(crate) number: String,
+    pub(crate) title: String,
+    pub(crate) event: Event,
+    /// The late deadline, if the calendar has a separate event for it
+    pub(crate) late: Option<Event>,
+}
 
-    writeln!(out, "{}", info.summary)?;
-    if let Some(due) = info.due {
-        write!(out, "Due: ")?;
+pub(crate) struct Due {
+    value: String,
+    tzid: Option<String>,
+}
 
-        PrimitiveDateTime::parse(
-            &due,
-            format_description!("[year][month][day]T[hour][minute][second]Z"),
-        )
-        .wrap_err("failed to parse time")?
-        .format_into(
-            &mut out,
-            &format_description::parse(&fmt).wrap_err("failed to parse time format")?,
+struct Summary {
+    number: String,
+    title: String,
+    late: bool,
+}
+
+impl Due {
+    fn from_prop(prop: &Property) -> Self {
+        Self {
+            value: prop.val.to_string(),
+            tzid: prop
+                .params
+                .iter()
+                .find(|param| param.key == "TZID")
+                .and_then(|param| param.val.as_ref())
+                .map(ToString::to_string),
+        }
+    }
+}
+
+impl Project {
+    pub(crate) fn url(&self) -> Option<&String> {
+        self.event
+            .url
+            .as_ref()
+            .or_else(|| self.late.as_ref()?.url.as_ref())
+    }
+}
+
+impl<W: Write> State<W> {
+    pub fn print_course_info(&mut self, props: &Props, cfg: &Config) -> Result<()> {
+        let project = find_project(props, read_events(&self.download_calendar(props, cfg)?)?)?;
+        let tz = time_zone(cfg)?;
+        let mut out = stdout().lock();
+
+        writeln!(out, "{}", project.event.summary)?;
+        if let Some(due) = &project.event.due {
+            let due = self.resolve_due(due, tz)?;
+            writeln!(out, "Due: {}", format_due(due, cfg)?)?;
+        }
+        if let Some(due) = project.late.as_ref().and_then(|late| late.due.as_ref()) {
+            let due = self.resolve_due(due, tz)?;
+            writeln!(out, "Late due: {}", format_due(due, cfg)?)?;
+        }
+        if let Some(description) = &project.event.description {
+            writeln!(out, "{description}")?;
+        }
+        if let Some(url) = project.url() {
+            writeln!(out, "{url}")?;
+        }
+
+        Ok(())
+    }
+
+    pub fn print_projects(&mut self, props: &Props, cfg: &Config) -> Result<()> {
+        let tz = time_zone(cfg)?;
+        let now = OffsetDateTime::now_utc();
+
+        let mut projects = Vec::new();
+        let calendar = self.download_calendar(props, cfg)?;
+        for project in get_projects(props, &calendar)? {
+            let due = project
+                .event
+                .due
+                .as_ref()
+                .map(|due| self.resolve_due(due, tz))
+                .transpose()?;
+
+            if match due {
+                Some(due) => cfg.past && due >= now || cfg.upcoming && due < now,
+                None => cfg.past || cfg.upcoming,
+            } {
+                continue;
+            }
+
+            let late = project
+                .late
+                .as_ref()
+                .and_then(|late| late.due.as_ref())
+                .map(|due| self.resolve_due(due, tz))
+                .transpose()?;
+
+            projects.push((due, late, project));
+        }
+
+        // projects without a due date go last
+        projects.sort_by_key(|(due, ..)| (due.is_none(), *due));
+
+        let mut out = stdout().lock();
+        for (due, late, project) in projects {
+            writeln!(out, "Project {}: {}", project.number, project.title)?;
+            if let Some(due) = due {
+                writeln!(out, "  Due: {}", format_due(due, cfg)?)?;
+            }
+            if let Some(late) = late {
+                writeln!(out, "  Late due: {}", format_due(late, cfg)?)?;
+            }
+            if let Some(url) = project.url() {
+                writeln!(out, "  {url}")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finds the due date of the project in the course calendar, formatted for display
+    pub(crate) fn project_due(&mut self, props: &Props, cfg: &Config) -> Result<Option<String>> {
+        let project = find_project(props, read_events(&self.download_calendar(props, cfg)?)?)?;
+        let tz = time_zone(cfg)?;
+        project
+            .event
+            .due
+            .as_ref()
+            .map(|due| format_due(self.resolve_due(due, tz)?, cfg))
+            .transpose()
+    }
+
+    pub fn get_course_url(&mut self, props: &Props, cfg: &Config) -> Result<String> {
+        let project = find_project(props, read_events(&self.download_calendar(props, cfg)?)?)?;
+        match project.url() {
+            Some(url) => Ok(url.clone()),
+            None => bail!("failed to find the project url in the course calendar"),
+        }
+    }
+
+    /// Parses the due date and converts it to the specified time zone,
+    /// or the local time zone if unspecified
+    pub(crate) fn resolve_due(&mut self, due: &Due, tz: Option<&Tz>) -> Result<OffsetDateTime> {
+        let due =
+            if let Some(due) = due.value.strip_suffix('Z') {
+                PrimitiveDateTime::parse(
+                    due,
+                    format_description!("[year][month][day]T[hour][minute][second]"),
+                )
+                .wrap_err("failed to parse time")?
+                .assume_utc()
+            } else {
+                let time = if due.value.len() == 8 {
+                    Date::parse(&due.value, format_description!("[year][month][day]"))
+                        .wrap_err("failed to parse time")?
+                        .with_time(Time::MIDNIGHT)
+                } else {
+                    PrimitiveDateTime::parse(
+                        &due.value,
+                        format_description!("[year][month][day]T[hour][minute][second]"),
+                    )
+                    .wrap_err("failed to parse time")?
+                };
+
+                // times without a TZID are floating and follow the time zone of the viewer
+                match (&due.tzid, tz) {
+                    (Some(tzid), _) => time
+                        .assume_timezone(timezones::get_by_name(tzid).ok_or_else(|| {
+                            eyre!("unknown time zone in the course calendar: {tzid}")
+                        })?)
+                        .take_first()
+                        .ok_or_else(|| eyre!("{time} does not exist in {tzid}"))?,
+                    (None, Some(tz)) => time
+                        .assume_timezone(tz)
+                        .take_first()
+                        .ok_or_else(|| eyre!("{time} does not exist in {}", tz.name()))?,
+                    (None, None) => {
+                        let utc = time.assume_utc();
+                        time.assume_offset(self.local_offset(utc))
+                    }
+                }
+            };
+
+        Ok(if let Some(tz) = tz {
+            due.to_timezone(tz)
+        } else {
+            due.to_offset(self.local_offset(due))
+        })
+    }
+
+    fn local_offset(&mut self, time: OffsetDateTime) -> UtcOffset {
+        UtcOffset::local_offset_at(time).unwrap_or_else(|e| {
+            warn!(
+                self,
+                "failed to determine the local time zone, using UTC instead: {e}"
+            );
+            UtcOffset::UTC
+        })
+    }
+}
+
+pub(crate) fn time_zone(cfg: &Config) -> Result<Option<&'static Tz>> {
+    cfg.time_zone
+        .as_deref()
+        .map(|name| timezones::get_by_name(name).ok_or_else(|| eyre!("unknown time zone: {name}")))
+        .transpose()
+}
+
+fn format_due(due: OffsetDateTime, cfg: &Config) -> Result<String> {
+    let mut s = due
+        .format(
+            &format_description::parse(&cfg.time_format).wrap_err("failed to parse time format")?,
         )
         .wrap_err("failed to format time")?;
 
-        writeln!(out)?;
+    if cfg.relative_time {
+        s.push_str(&format!(
+            " ({})",
+            format_relative(due - OffsetDateTime::now_utc())
+        ));
     }
-    if let Some(description) = info.description {
-        // https://github.com/hoodie/icalendar-rs/issues/53
-        writeln!(out, "{}", description.replace('\\', ""))?;
+
+    Ok(s)
+}
+
+fn format_relative(diff: Duration) -> String {
+    const UNITS: [(u64, &str); 3] = [(86400, "day"), (3600, "hour"), (60, "minute")];
+
+    let secs = diff.whole_seconds().unsigned_abs();
+    let Some(i) = UNITS.iter().position(|(n, _)| secs >= *n) else {
+        return "now".into();
+    };
+
+    let mut secs = secs;
+    let mut parts = Vec::with_capacity(2);
+    for (n, unit) in &UNITS[i .. (i + 2).min(UNITS.len())] {
+        let x = secs / n;
+        secs %= n;
+        if x != 0 {
+            parts.push(format!("{x} {unit}{}", if x == 1 { "" } else { "s" }));
+        }
     }
-    if let Some(url) = info.url {
-        writeln!(out, "{url}")?;
+
+    let parts = parts.join(" ");
+    if diff.is_positive() {
+        format!("in {parts}")
+    } else {
+        format!("{parts} ago")
     }
+}
 
-    Ok(())
+pub(crate) fn get_projects(props: &Props, calendar: &str) -> Result<Vec<Project>> {
+    Ok(group_projects(
+        read_events(calendar)?,
+        props.get_prop("courseName")?,
+    ))
 }
 
-fn get_course_info(props: &Props) -> Result<CourseInfo> {
-    get_course_props(
-        props,
-        |component, prefix| {
-            let mut summary = None;
-            let mut due = None;
-            let mut description = None;
-            let mut url = None;
+/// Finds the events for the project described by .submit
+fn find_project(props: &Props, events: Vec<Event>) -> Result<Project> {
+    let course = props.get_prop("courseName")?;
+    let number = props.get_prop("projectNumber")?;
 
-            for prop in component.properties.iter() {
-                match prop.name.as_str() {
-                    "SUMMARY" => {
-                        let s = prop.val.to_string();
-                        if s.starts_with(prefix) {
-                            summary = Some(s);
-                        } else {
-                            return None;
-                        }
-                    }
+    let summaries: Vec<_> = events.iter().map(|event| event.summary.clone()).collect();
+    let (matched, rest): (Vec<_>, Vec<_>) = events.into_iter().partition(|event| {
+        parse_summary(&event.summary, course)
+            .is_some_and(|summary| same_number(&summary.number, number))
+    });
 
-                    "DTSTART" => due = Some(prop.val.to_string()),
+    if let Some(project) = group_projects(matched, course).into_iter().next() {
+        return Ok(project);
+    }
+
+    // fall back to events that mention the project in their UID or URL
+    let (mut late, mut on_time): (Vec<_>, Vec<_>) = rest
+        .into_iter()
+        .filter(|event| has_hint(event, number))
+        .partition(|event| is_late(&event.summary));
+    if let Some(event) = on_time.pop().or_else(|| late.pop()) {
+        return Ok(Project {
+            number: number.clone(),
+            title: event.summary.clone(),
+            event,
+            late: late.pop(),
+        });
+    }
 
-                    "DESCRIPTION" => description = Some(prop.val.to_string()),
+    let mut candidates: Vec<_> = summaries
+        .iter()
+        .filter(|summary| parse_summary(summary, course).is_some())
+        .collect();
+    if candidates.is_empty() {
+        candidates = summaries.iter().collect();
+    }
 
-                    "URL" => url = Some(prop.val.to_string()),
+    if candidates.is_empty() {
+        bail!("failed to find {course} project {number}, the course calendar has no events");
+    }
 
-                    _ => {}
-                }
+    let mut msg =
+        format!("failed to find {course} project {number}, candidates in the course calendar:");
+    for candidate in candidates {
+        msg.push_str("\n- ");
+        msg.push_str(candidate);
+    }
+    bail!(msg)
+}
+
+/// Groups the events of the course by project number,
+/// separating on-time deadlines from late deadlines
+fn group_projects(events: Vec<Event>, course: &str) -> Vec<Project> {
+    let mut entries: Vec<(Summary, Option<Event>, Option<Event>)> = Vec::new();
+
+    for event in events {
+        let Some(summary) = parse_summary(&event.summary, course) else {
+            continue;
+        };
+
+        let i = match entries
+            .iter()
+            .position(|(entry, ..)| same_number(&entry.number, &summary.number))
+        {
+            Some(i) => i,
+            None => {
+                entries.push((
+                    Summary {
+                        number: summary.number.clone(),
+                        title: summary.title.clone(),
+                        late: true,
+                    },
+                    None,
+                    None,
+                ));
+                entries.len() - 1
             }
+        };
 
-            summary.map(|summary| CourseInfo {
-                summary,
-                due,
-                description,
-                url,
+        let (entry, on_time, late) = &mut entries[i];
+        if summary.late {
+            late.get_or_insert(event);
+        } else if on_time.is_none() {
+            // prefer the title of the on-time deadline
+            entry.title = summary.title;
+            *on_time = Some(event);
+        }
+    }
+
+    entries
+        .into_iter()
+        .filter_map(|(summary, on_time, mut late)| {
+            let event = match on_time {
+                Some(event) => event,
+                None => late.take()?,
+            };
+
+            Some(Project {
+                number: summary.number,
+                title: summary.title,
+                event,
+                late,
             })
-        },
-        || eyre!("failed to find information for the course"),
-    )
+        })
+        .collect()
 }
 
-pub fn get_course_url(props: &Props) -> Result<String> {
-    get_course_props(
-        props,
-        |component, prefix| {
+/// Parses summaries like "CMSC132 project 1: Title",
+/// ignoring differences in case and whitespace
+fn parse_summary(summary: &str, course: &str) -> Option<Summary> {
+    let summary = normalize(summary);
+    let rest = strip_word(&summary, &normalize(course))?;
+    let rest = strip_word(rest, "project")?;
+
+    let (head, title) = rest.split_once(':').unwrap_or((rest, ""));
+    let mut words = head.split(' ');
+    let number = words.next().filter(|number| !number.is_empty())?;
+
+    Some(Summary {
+        number: number.into(),
+        title: title.trim().into(),
+        late: words.any(|word| word.eq_ignore_ascii_case("late")) || is_late(title),
+    })
+}
+
+fn normalize(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Strips a case-insensitive prefix that is followed by a word boundary
+fn strip_word<'a>(s: &'a str, word: &str) -> Option<&'a str> {
+    let rest = s
+        .get(.. word.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(word))
+        .map(|_| &s[word.len() ..])?;
+
+    (!rest.starts_with(|c: char| c.is_alphanumeric())).then(|| rest.trim_start())
+}
+
+fn is_late(s: &str) -> bool {
+    let s = normalize(s).to_lowercase();
+    ["late deadline", "late due", "late submission", "(late)"]
+        .iter()
+        .any(|pattern| s.contains(pattern))
+}
+
+fn same_number(x: &str, y: &str) -> bool {
+    x.eq_ignore_ascii_case(y)
+        || matches!((x.parse::<u64>(), y.parse::<u64>()), (Ok(x), Ok(y)) if x == y)
+}
+
+/// Checks if the UID or URL of the event mentions the project,
+/// e.g. `projectNumber=1` or `project-1`
+fn has_hint(event: &Event, number: &str) -> bool {
+    [&event.uid, &event.url].into_iter().flatten().any(|s| {
+        let words: Vec<_> = s
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        words.windows(2).any(|words| {
+            (words[0].eq_ignore_ascii_case("project")
+                || words[0].eq_ignore_ascii_case("projectNumber"))
+                && same_number(words[1], number)
+        })
+    })
+}
+
+fn read_events(calendar: &str) -> Result<Vec<Event>> {
+    let calendar = hide_backslashes(&unfold(calendar));
+    let mut events = Vec::new();
+
+    for root in parse_calendar(&calendar)? {
+        for component in root.components.iter() {
+            let mut summary = None;
+            let mut due = None;
+            let mut description = None;
             let mut url = None;
-            let mut found = false;
+            let mut uid = None;
 
             for prop in component.properties.iter() {
                 match prop.name.as_str() {
-                    "SUMMARY" => {
-                        if prop.val.as_str().starts_with(prefix) {
-                            found = true;
-                        } else {
-                            return None;
-                        }
+                    "SUMMARY" => summary = Some(unescape(prop.val.as_str())),
+
+                    "DTSTART" => due = Some(Due::from_prop(prop)),
+
+                    "DESCRIPTION" => {
+                        description = Some(html_to_text(&unescape(prop.val.as_str())));
                     }
 
-                    "URL" => url = Some(prop.val.to_string()),
+                    "URL" => url = Some(restore_backslashes(prop.val.as_str())),
+
+                    "UID" => uid = Some(unescape(prop.val.as_str())),
 
                     _ => {}
                 }
             }
 
-            if found {
-                url
-            } else {
-                None
+            if let Some(summary) = summary {
+                events.push(Event {
+                    summary,
+                    due,
+                    description,
+                    url,
+                    uid,
+                });
             }
-        },
-        || eyre!("failed to find the course url"),
-    )
-}
-
-fn get_course_props<A>(
-    props: &Props,
-    f: fn(&Component, &str) -> Option<A>,
-    e: fn() -> Report,
-) -> Result<A> {
-    let prefix = format!(
-        "{} project {}: ",
-        props.get_prop("courseName")?,
-        props.get_prop("projectNumber")?,
-    );
-
-    read_calendar_simple(&unfold(
-        &ureq::get(&format!(
-            "{}/feed/CourseCalendar?courseKey={}",
-            props.get_prop("baseURL")?,
-            props.get_prop("courseKey")?,
-        ))
-        .call()
-        .wrap_err("failed to download the course calendar")?
-        .into_string()
-        .wrap_err("failed to parse the course calendar")?,
-    ))
-    .map_err(|e| eyre!("{e}").wrap_err("failed to parse the course calendar"))?
-    .first()
-    .and_then(|root| {
-        root.components
-            .iter()
-            .find_map(|component| f(component, &prefix))
-    })
-    .ok_or_else(e)
+        }
+    }
+
+    Ok(events)
+}
+
+fn parse_calendar(calendar: &str) -> Result<Vec<Component<'_>>> {
+    // the parser fails to parse anything when there are multiple calendars,
+    // so each calendar is parsed separately
+    let mut starts: Vec<_> = calendar
+        .match_indices("BEGIN:VCALENDAR")
+        .map(|(i, _)| i)
+        .filter(|&i| i == 0 || calendar[.. i].ends_with('\n'))
+        .skip(1)
+        .collect();
+    starts.insert(0, 0);
+    starts.push(calendar.len());
+
+    let mut roots = Vec::new();
+    for range in starts.windows(2) {
+        roots.extend(
+            read_calendar_simple(&calendar[range[0] .. range[1]])
+                .map_err(|e| eyre!("{e}").wrap_err("failed to parse the course calendar"))?,
+        );
+    }
+
+    Ok(roots)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration};
+    use time_tz::timezones::db::america::NEW_YORK;
+
+    use super::{find_project, format_relative, group_projects, read_events, Due};
+    use crate::{state::State, Props};
+
+    const CALENDAR: &str = include_str!("../tests/fixtures/calendar.ics");
+
+    fn props(project: &str) -> Props {
+        [("courseName", "CMSC132"), ("projectNumber", project)]
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect()
+    }
+
+    fn due(value: &str, tzid: Option<&str>) -> Due {
+        Due {
+            value: value.into(),
+            tzid: tzid.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn resolve_due_utc() {
+        assert_eq!(
+            State::sink()
+                .resolve_due(&due("20221026T035900Z", None), Some(NEW_YORK))
+                .unwrap(),
+            datetime!(2022-10-25 23:59 -4),
+        );
+    }
+
+    #[test]
+    fn resolve_due_tzid() {
+        assert_eq!(
+            State::sink()
+                .resolve_due(
+                    &due("20221225T235900", Some("America/Los_Angeles")),
+                    Some(NEW_YORK),
+                )
+                .unwrap(),
+            datetime!(2022-12-26 02:59 -5),
+        );
+    }
+
+    #[test]
+    fn resolve_due_floating() {
+        assert_eq!(
+            State::sink()
+                .resolve_due(&due("20221025T235900", None), Some(NEW_YORK))
+                .unwrap(),
+            datetime!(2022-10-25 23:59 -4),
+        );
+    }
+
+    #[test]
+    fn resolve_due_date() {
+        assert_eq!(
+            State::sink()
+                .resolve_due(&due("20221025", None), Some(NEW_YORK))
+                .unwrap(),
+            datetime!(2022-10-25 0:00 -4),
+        );
+    }
+
+    #[test]
+    fn relative() {
+        assert_eq!(format_relative(Duration::seconds(30)), "now");
+        assert_eq!(format_relative(Duration::minutes(5)), "in 5 minutes");
+        assert_eq!(
+            format_relative(Duration::days(2) + Duration::hours(3) + Duration::minutes(4)),
+            "in 2 days 3 hours",
+        );
+        assert_eq!(format_relative(-Duration::hours(1)), "1 hour ago");
+        assert_eq!(
+            format_relative(Duration::days(1) + Duration::minutes(4)),
+            "in 1 day"
+        );
+    }
+
+    fn make_calendar(events: &[&[&str]]) -> String {
+        let mut calendar = String::from("BEGIN:VCALENDAR\r\n");
+        for event in events {
+            calendar.push_str("BEGIN:VEVENT\r\n");
+            for prop in *event {
+                calendar.push_str(prop);
+                calendar.push_str("\r\n");
+            }
+            calendar.push_str("END:VEVENT\r\n");
+        }
+        calendar.push_str("END:VCALENDAR\r\n");
+        calendar
+    }
+
+    #[test]
+    fn projects() {
+        let calendar = make_calendar(&[
+            &[
+                "SUMMARY:CMSC132 project 1: Foo",
+                "DTSTART:20221026T035900Z",
+                "URL:https://example.com/1",
+            ],
+            &["SUMMARY:CMSC216 project 1: Bar"],
+            &["SUMMARY:CMSC132 project 2a: Baz: Qux"],
+        ]);
+        let projects = group_projects(read_events(&calendar).unwrap(), "CMSC132");
+
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].number, "1");
+        assert_eq!(projects[0].title, "Foo");
+        assert_eq!(
+            projects[0].url().map(String::as_str),
+            Some("https://example.com/1"),
+        );
+        assert!(projects[0].event.due.is_some());
+        assert_eq!(projects[1].number, "2a");
+        assert_eq!(projects[1].title, "Baz: Qux");
+        assert!(projects[1].event.due.is_none());
+    }
+
+    #[test]
+    fn projects_fixture() {
+        let projects = group_projects(read_events(CALENDAR).unwrap(), "CMSC132");
+
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].title, "Linked Lists, Part 1");
+        assert_eq!(
+            projects[0].event.uid.as_deref(),
+            Some("project-101@submit.cs.umd.edu"),
+        );
+        assert_eq!(projects[1].number, "2");
+        assert_eq!(
+            projects[1].event.due.as_ref().unwrap().tzid.as_deref(),
+            Some("America/New_York"),
+        );
+    }
+
+    #[test]
+    fn info_escaped() {
+        let project = find_project(&props("1"), read_events(CALENDAR).unwrap()).unwrap();
+        assert_eq!(
+            project.event.summary,
+            "CMSC132 project 1: Linked Lists, Part 1",
+        );
+        assert_eq!(
+            project.event.description.as_deref(),
+            Some("Implement a doubly linked list; see the spec.\nUse C:\\Users\\you, not C:\\\\n."),
+        );
+    }
+
+    #[test]
+    fn info_html_second_calendar() {
+        let project = find_project(&props("2"), read_events(CALENDAR).unwrap()).unwrap();
+        assert_eq!(project.event.summary, "CMSC132 project 2: Trees");
+        assert_eq!(
+            project.event.description.as_deref(),
+            Some("Implement a binary search tree.\n- insert & remove\n- traversal"),
+        );
+        assert_eq!(
+            project.url().map(String::as_str),
+            Some("https://submit.cs.umd.edu/fall2022/view/project.jsp?projectPK=102"),
+        );
+    }
+
+    #[test]
+    fn info_missing() {
+        let Err(e) = find_project(&props("3"), read_events(CALENDAR).unwrap()) else {
+            panic!("project 3 should not be found");
+        };
+        let e = e.to_string();
+        assert!(e.contains("- CMSC132 project 1: Linked Lists, Part 1"));
+        assert!(e.contains("- CMSC132 project 2: Trees"));
+    }
+
+    #[test]
+    fn match_case_and_whitespace() {
+        let calendar = make_calendar(&[&["SUMMARY:cmsc132   Project  P1a :  Foo"]]);
+        let project = find_project(&props("p1a"), read_events(&calendar).unwrap()).unwrap();
+        assert_eq!(project.number, "P1a");
+        assert_eq!(project.title, "Foo");
+
+        let calendar = make_calendar(&[&["SUMMARY:CMSC1320 project 1: Foo"]]);
+        assert!(find_project(&props("1"), read_events(&calendar).unwrap()).is_err());
+    }
+
+    #[test]
+    fn match_late_deadline() {
+        let calendar = make_calendar(&[
+            &[
+                "SUMMARY:CMSC132 project 1 late deadline: Foo",
+                "DTSTART:20221028T035900Z",
+            ],
+            &["SUMMARY:CMSC132 project 1: Foo", "DTSTART:20221026T035900Z"],
+        ]);
+        let project = find_project(&props("1"), read_events(&calendar).unwrap()).unwrap();
+        assert_eq!(project.event.summary, "CMSC132 project 1: Foo");
+        assert_eq!(project.late.unwrap().due.unwrap().value, "20221028T035900Z",);
+    }
+
+    #[test]
+    fn match_hint() {
+        let calendar = make_calendar(&[
+            &[
+                "SUMMARY:Homework",
+                "URL:https://example.com/view?projectNumber=2",
+            ],
+            &[
+                "SUMMARY:Linked lists",
+                "URL:https://example.com/view?projectNumber=1",
+            ],
+        ]);
+        let project = find_project(&props("1"), read_events(&calendar).unwrap()).unwrap();
+        assert_eq!(project.event.summary, "Linked lists");
+    }
 }
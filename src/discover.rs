@@ -0,0 +1,193 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+};
+
+use eyre::{bail, Result, WrapErr};
+use ignore::WalkBuilder;
+
+use crate::{cli::StopAt, config::Config, state::State, warn, Props, PropsExt};
+
+impl<W: Write> State<W> {
+    /// Finds the .submit file, searching the parent directories of the start directory
+    /// up to the configured boundary and asking for confirmation if it is not in the start directory
+    pub fn find_submit_file(&mut self, start: &Path, cfg: &Config) -> Result<PathBuf> {
+        if let Some(path) = &cfg.submit_file {
+            if !path.is_file() {
+                bail!("{} is not a file", path.display());
+            }
+            return Ok(path.clone());
+        }
+
+        let home = home_dir();
+        let mut dir = start;
+        let mut depth = 0;
+        let path = loop {
+            let path = dir.join(".submit");
+            if path.is_file() {
+                break path;
+            }
+
+            let boundary = match cfg.stop_at {
+                StopAt::Git => dir.join(".git").exists(),
+                StopAt::Home => home.as_deref() == Some(dir),
+                StopAt::Root => false,
+            };
+            if boundary || cfg.max_depth.is_some_and(|max| depth >= max) {
+                bail!(
+                    "failed to find .submit, stopped searching at {}",
+                    dir.display(),
+                );
+            }
+            let Some(parent) = dir.parent() else {
+                bail!("failed to find .submit");
+            };
+
+            warn!(
+                self,
+                "no .submit file found in {}, trying {}",
+                dir.display(),
+                parent.display(),
+            );
+            dir = parent;
+            depth += 1;
+        };
+
+        if dir != start && !cfg.yes {
+            if !io::stdin().is_terminal() {
+                bail!(
+                    "refusing to use {} without confirmation because stdin is not a terminal, \
+                    use --yes to use it anyway or --submit-file to specify the .submit file",
+                    path.display(),
+                );
+            }
+            self.prompt(format_args!("Use {}? [y/N]", path.display()))?;
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .wrap_err("failed to prompt for confirmation")?;
+            if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+                bail!("aborted, use --submit-file to specify the .submit file");
+            }
+        }
+
+        Ok(path)
+    }
+}
+
+#[cfg(unix)]
+fn home_dir() -> Option<PathBuf> {
+    #[allow(deprecated)]
+    std::env::home_dir()
+}
+
+#[cfg(windows)]
+fn home_dir() -> Option<PathBuf> {
+    dirs::home_dir()
+}
+
+/// Finds all .submit files under the current directory
+pub fn find_submit_files() -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+
+    for entry in WalkBuilder::new(".")
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+    {
+        let entry = entry.wrap_err("failed to read entry")?;
+        if entry.file_name() == ".submit" && entry.path().is_file() {
+            paths.push(entry.into_path().strip_prefix(".")?.into());
+        }
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+/// Reads the .submit file at the path without checking it for problems,
+/// which only matters when submitting
+pub(crate) fn read_submit_file(path: &Path) -> Result<Props> {
+    java_properties::read(
+        File::open(path).wrap_err_with(|| format!("failed to read {}", path.display()))?,
+    )
+    .wrap_err_with(|| format!("failed to parse {}", path.display()))
+}
+
+/// Reads the .submit files under the current directory, keeping one per course
+pub fn find_courses() -> Result<Vec<Props>> {
+    let mut seen = HashSet::new();
+    let mut courses = Vec::new();
+
+    for path in find_submit_files()? {
+        let props = read_submit_file(&path)?;
+
+        if seen.insert((
+            props.get_prop("baseURL")?.clone(),
+            props.get_prop("courseKey")?.clone(),
+        )) {
+            courses.push(props);
+        }
+    }
+
+    Ok(courses)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use crate::{cli::StopAt, config::Config, state::State};
+
+    #[test]
+    fn search_boundary() {
+        let dir = tempdir().unwrap();
+        let start = dir.path().join("project/src/main");
+        fs::create_dir_all(&start).unwrap();
+        fs::create_dir_all(dir.path().join("project/src/.git")).unwrap();
+        fs::write(dir.path().join("project/.submit"), "").unwrap();
+
+        let find = |cfg| State::sink().find_submit_file(&start, &cfg);
+        let yes = |cfg| Config { yes: true, ..cfg };
+
+        assert_eq!(
+            find(yes(Config::default())).unwrap(),
+            dir.path().join("project/.submit"),
+        );
+        assert!(find(yes(Config {
+            max_depth: Some(1),
+            ..Default::default()
+        }))
+        .is_err());
+        assert!(find(yes(Config {
+            max_depth: Some(2),
+            ..Default::default()
+        }))
+        .is_ok());
+        assert!(find(yes(Config {
+            stop_at: StopAt::Git,
+            ..Default::default()
+        }))
+        .is_err());
+
+        let submit_file = start.join("other.submit");
+        assert!(find(Config {
+            submit_file: Some(submit_file.clone()),
+            ..Default::default()
+        })
+        .is_err());
+        fs::write(&submit_file, "").unwrap();
+        assert_eq!(
+            find(Config {
+                submit_file: Some(submit_file.clone()),
+                ..Default::default()
+            })
+            .unwrap(),
+            submit_file,
+        );
+    }
+}
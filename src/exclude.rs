@@ -0,0 +1,185 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use eyre::{Result, WrapErr};
+use ignore::{
+    gitignore::{Gitignore, GitignoreBuilder},
+    Match,
+};
+
+use crate::required::PROJECT_CONFIG;
+
+/// The ignore files that decide which files under the root are not submitted,
+/// respected the same way git and ripgrep respect them
+pub(crate) struct Rules {
+    root: PathBuf,
+    git_root: Option<PathBuf>,
+    exclude: Option<Gitignore>,
+    global: Option<Gitignore>,
+    dirs: HashMap<PathBuf, (Gitignore, Option<Gitignore>)>,
+}
+
+impl Rules {
+    pub(crate) fn new(root: &Path) -> Result<Self> {
+        let root = root
+            .canonicalize()
+            .wrap_err_with(|| format!("failed to resolve {}", root.display()))?;
+        let git_root = root
+            .ancestors()
+            .find(|dir| dir.join(".git").exists())
+            .map(Path::to_owned);
+
+        let exclude = git_root.as_ref().and_then(|git_root| {
+            let mut exclude = GitignoreBuilder::new(git_root);
+            exclude.add(git_root.join(".git/info/exclude"));
+            exclude.build().ok()
+        });
+        let global = git_root.as_ref().map(|_| Gitignore::global().0);
+
+        Ok(Self {
+            root,
+            git_root,
+            exclude,
+            global,
+            dirs: HashMap::new(),
+        })
+    }
+
+    /// The canonical path of the root
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns why the path relative to the root or one of its parent directories
+    /// is never submitted or ignored
+    pub(crate) fn excluded_with_parents(&mut self, path: &Path, is_dir: bool) -> Option<String> {
+        let mut parents: Vec<_> = path.ancestors().skip(1).collect();
+        parents.pop();
+        parents
+            .into_iter()
+            .rev()
+            .find_map(|parent| self.excluded(parent, true))
+            .or_else(|| self.excluded(path, is_dir))
+    }
+
+    /// Returns why the path relative to the root is never submitted,
+    /// or why it is ignored, without checking its parent directories
+    pub(crate) fn excluded(&mut self, path: &Path, is_dir: bool) -> Option<String> {
+        if path == Path::new(".git") {
+            return Some("always excluded".into());
+        }
+        if path.file_name().is_some_and(|name| name == ".submitUser") {
+            return Some("always excluded, contains credentials".into());
+        }
+        if path == Path::new(PROJECT_CONFIG) {
+            return Some("always excluded, configures sagoin".into());
+        }
+
+        let full = self.root.join(path);
+        let mut dirs = Vec::new();
+        for dir in full.ancestors().skip(1) {
+            dirs.push(dir);
+            if Some(dir) == self.git_root.as_deref() || self.git_root.is_none() && dir == self.root
+            {
+                break;
+            }
+        }
+
+        let git = self.git_root.is_some();
+        for dir in &dirs {
+            self.dirs.entry(dir.to_path_buf()).or_insert_with(|| {
+                (
+                    Gitignore::new(dir.join(".ignore")).0,
+                    git.then(|| Gitignore::new(dir.join(".gitignore")).0),
+                )
+            });
+        }
+
+        let (mut ignore, mut gitignore) = (Match::None, Match::None);
+        for dir in dirs {
+            let (dir_ignore, dir_gitignore) = &self.dirs[dir];
+            // the deepest ignore file with a matching pattern decides
+            if ignore.is_none() {
+                ignore = dir_ignore.matched(&full, is_dir);
+            }
+            if gitignore.is_none() {
+                if let Some(dir_gitignore) = dir_gitignore {
+                    gitignore = dir_gitignore.matched(&full, is_dir);
+                }
+            }
+        }
+
+        let matched = ignore
+            .or(gitignore)
+            .or(self
+                .exclude
+                .as_ref()
+                .map_or(Match::None, |exclude| exclude.matched(&full, is_dir)))
+            .or(self
+                .global
+                .as_ref()
+                .map_or(Match::None, |global| global.matched(&full, is_dir)));
+
+        let Match::Ignore(glob) = matched else {
+            return None;
+        };
+        let from = glob.from().map_or_else(
+            || "global git ignore".into(),
+            |from| {
+                from.strip_prefix(&self.root)
+                    .unwrap_or(from)
+                    .display()
+                    .to_string()
+            },
+        );
+        Some(format!("ignored by {from}: {}", glob.original()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use tempfile::tempdir;
+
+    use super::Rules;
+
+    #[test]
+    fn precedence() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("project");
+        fs::create_dir_all(dir.path().join(".git/info")).unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(dir.path().join(".git/info/exclude"), "*.log\n").unwrap();
+        fs::write(dir.path().join(".gitignore"), "*.o\n/project/out\n").unwrap();
+        fs::write(root.join(".gitignore"), "!keep.o\n").unwrap();
+        fs::write(root.join("src/.ignore"), "*.tmp\n!important.log\n").unwrap();
+
+        let mut rules = Rules::new(&root).unwrap();
+        let mut excluded = |path: &str, is_dir| rules.excluded(Path::new(path), is_dir);
+
+        assert_eq!(excluded("Main.java", false), None);
+        assert_eq!(excluded(".git", true).as_deref(), Some("always excluded"));
+        assert_eq!(
+            excluded("a/.submitUser", false).as_deref(),
+            Some("always excluded, contains credentials"),
+        );
+        assert!(excluded("main.o", false)
+            .unwrap()
+            .ends_with(".gitignore: *.o"));
+        assert_eq!(excluded("keep.o", false), None);
+        assert!(excluded("out", true)
+            .unwrap()
+            .ends_with(".gitignore: /project/out"));
+        assert!(excluded("build.log", false)
+            .unwrap()
+            .ends_with("info/exclude: *.log"));
+        assert_eq!(
+            excluded("src/a.tmp", false).as_deref(),
+            Some("ignored by src/.ignore: *.tmp"),
+        );
+        assert_eq!(excluded("src/important.log", false), None);
+    }
+}
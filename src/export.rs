@@ -0,0 +1,85 @@
+use std::{fs, io::Write, path::Path};
+
+use chrono::TimeDelta;
+use eyre::{bail, Result, WrapErr};
+use icalendar::{Alarm, Calendar, Component, EventLike, Trigger};
+use time::{macros::format_description, UtcOffset};
+
+use crate::{
+    config::Config,
+    course::{get_projects, time_zone, Event},
+    state::State,
+    Props, PropsExt,
+};
+
+impl<W: Write> State<W> {
+    /// Writes the projects of the courses to an iCalendar file,
+    /// or stdout if the path is `-`
+    pub fn export_calendar(&mut self, courses: &[Props], cfg: &Config, path: &Path) -> Result<()> {
+        let tz = time_zone(cfg)?;
+        let mut calendar = Calendar::new();
+
+        for props in courses {
+            let course = props.get_prop("courseName")?;
+
+            let feed = self.download_calendar(props, cfg)?;
+            for project in get_projects(props, &feed)? {
+                let url = project.url().cloned();
+                let events = [(Some(project.event), ""), (project.late, "-late")];
+
+                for (event, suffix) in events {
+                    let Some(Event {
+                        summary,
+                        due: Some(due),
+                        uid,
+                        ..
+                    }) = event
+                    else {
+                        continue;
+                    };
+
+                    let due = self
+                        .resolve_due(&due, tz)?
+                        .to_offset(UtcOffset::UTC)
+                        .format(format_description!(
+                            "[year][month][day]T[hour][minute][second]Z"
+                        ))
+                        .wrap_err("failed to format time")?;
+
+                    let mut exported = icalendar::Event::new();
+                    exported
+                        .uid(&uid.unwrap_or_else(|| {
+                            format!("{course}-project-{}{suffix}@sagoin", project.number)
+                                .replace(' ', "-")
+                        }))
+                        .summary(&summary)
+                        .add_property("DTSTART", &due)
+                        .add_property("DTEND", &due);
+
+                    if let Some(url) = &url {
+                        exported.url(url);
+                    }
+
+                    for reminder in &cfg.reminders {
+                        let Some(before) = TimeDelta::try_seconds(reminder.whole_seconds()) else {
+                            bail!("reminder is too far before the due date: {reminder}");
+                        };
+                        exported.alarm(Alarm::display(&summary, Trigger::before_start(before)));
+                    }
+
+                    calendar.push(exported.done());
+                }
+            }
+        }
+
+        let calendar = calendar.done().to_string();
+        if path == Path::new("-") {
+            write!(std::io::stdout().lock(), "{calendar}")?;
+        } else {
+            fs::write(path, calendar)
+                .wrap_err_with(|| format!("failed to write to {}", path.display()))?;
+        }
+
+        Ok(())
+    }
+}
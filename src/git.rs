@@ -0,0 +1,412 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+};
+
+use eyre::{bail, Result, WrapErr};
+use tempfile::TempDir;
+
+use crate::{
+    archive::walk, config::Config, exclude::Rules, required::PROJECT_CONFIG, state::State, warn,
+};
+
+struct GitStatus {
+    commit: Option<String>,
+    changed: Vec<PathBuf>,
+    untracked: Vec<PathBuf>,
+}
+
+impl<W: Write> State<W> {
+    /// Reports the current commit and the changes that are not committed,
+    /// returning the current commit if there is one
+    pub(crate) fn check_git(&mut self, root: &Path, cfg: &Config) -> Result<Option<String>> {
+        let Some(status) = git_status(root, cfg)? else {
+            warn!(
+                self,
+                "not in a git repository, skipping the git status check"
+            );
+            return Ok(None);
+        };
+
+        if let Some(commit) = &status.commit {
+            writeln!(self.out, "Commit: {commit}")?;
+        } else {
+            warn!(self, "no commits in the git repository yet");
+        }
+
+        if !status.changed.is_empty() {
+            warn!(self, "uncommitted changes:");
+            for path in &status.changed {
+                writeln!(self.out, "  {}", path.display())?;
+            }
+        }
+
+        if !status.untracked.is_empty() {
+            warn!(self, "untracked files that will be submitted:");
+            for path in &status.untracked {
+                writeln!(self.out, "  {}", path.display())?;
+            }
+        }
+
+        Ok(status.commit)
+    }
+}
+
+/// Extracts the files tracked at the revision under the root to a temporary directory,
+/// returning the directory and the full hash of the commit
+pub(crate) fn export_rev(root: &Path, rev: &str) -> Result<(TempDir, String)> {
+    let Some(commit) = git(
+        root,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{rev}^{{commit}}"),
+        ],
+    ) else {
+        bail!("{rev} is not a commit in the git repository");
+    };
+    let commit = commit.trim_end().to_owned();
+    let Some(prefix) = git(root, &["rev-parse", "--show-prefix"]) else {
+        bail!("{} is not in a git repository", root.display());
+    };
+
+    // listing the tree instead of using git archive respects the ignore files outside of the root
+    // and in .git/info/exclude the same way as submitting the working tree, without export-ignore
+    let Some(tree) = git(
+        root,
+        &[
+            "ls-tree",
+            "-r",
+            "-z",
+            // without this, only the paths under the prefix of the subtree are listed
+            "--full-tree",
+            &format!("{commit}:{}", prefix.trim_end()),
+        ],
+    ) else {
+        bail!("failed to list the files at {rev}");
+    };
+
+    let mut rules = Rules::new(root)?;
+    let mut entries = Vec::new();
+    for entry in tree.split_terminator('\0') {
+        let Some((info, path)) = entry.split_once('\t') else {
+            continue;
+        };
+        let mut info = info.split(' ');
+        let (Some(mode), Some("blob"), Some(object)) = (info.next(), info.next(), info.next())
+        else {
+            // submodules are not submitted
+            continue;
+        };
+        // the project config is never submitted, but it declares the required files
+        if path == PROJECT_CONFIG
+            || rules
+                .excluded_with_parents(Path::new(path), false)
+                .is_none()
+        {
+            entries.push((mode.to_owned(), object.to_owned(), PathBuf::from(path)));
+        }
+    }
+
+    let dir = tempfile::tempdir().wrap_err("failed to create a temporary directory")?;
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .wrap_err("failed to run git")?;
+
+    let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        bail!("failed to run git");
+    };
+
+    // writing the objects in another thread keeps git from blocking on a full stdout pipe
+    let objects: Vec<_> = entries
+        .iter()
+        .map(|(_, object, _)| object.clone())
+        .collect();
+    let writer = thread::spawn(move || -> io::Result<()> {
+        for object in objects {
+            writeln!(stdin, "{object}")?;
+        }
+        Ok(())
+    });
+
+    let mut stdout = BufReader::new(stdout);
+    for (mode, _, path) in &entries {
+        let contents = read_object(&mut stdout)
+            .wrap_err_with(|| format!("failed to read {} at {rev}", path.display()))?;
+        write_entry(&dir.path().join(path), mode, &contents)
+            .wrap_err_with(|| format!("failed to extract {} at {rev}", path.display()))?;
+    }
+
+    let _ = writer.join();
+    child.wait().wrap_err("failed to run git")?;
+
+    Ok((dir, commit))
+}
+
+/// Reads the next object from the output of git cat-file --batch
+fn read_object(stdout: &mut impl BufRead) -> Result<Vec<u8>> {
+    let mut header = String::new();
+    stdout.read_line(&mut header)?;
+    let Some(size) = header
+        .split(' ')
+        .nth(2)
+        .and_then(|size| size.trim_end().parse().ok())
+    else {
+        bail!("unexpected output from git: {}", header.trim_end());
+    };
+
+    let mut contents = vec![0; size];
+    stdout.read_exact(&mut contents)?;
+    stdout.read_exact(&mut [0])?;
+    Ok(contents)
+}
+
+fn write_entry(path: &Path, mode: &str, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if mode == "120000" {
+        symlink(&String::from_utf8_lossy(contents), path)?;
+    } else {
+        fs::write(path, contents)?;
+        if mode == "100755" {
+            set_executable(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink(target: &str, path: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, path)
+}
+
+#[cfg(windows)]
+fn symlink(target: &str, path: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, path)
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(windows)]
+fn set_executable(_: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Runs git under the root, returning the output if it succeeds
+fn git(root: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+fn git_status(root: &Path, cfg: &Config) -> Result<Option<GitStatus>> {
+    let Some(prefix) = git(root, &["rev-parse", "--show-prefix"]) else {
+        return Ok(None);
+    };
+    let prefix = prefix.trim_end();
+
+    let commit = git(root, &["rev-parse", "--verify", "--quiet", "HEAD"])
+        .map(|commit| commit.trim_end().to_owned());
+
+    let Some(status) = git(
+        root,
+        &[
+            "status",
+            "--porcelain",
+            "-z",
+            "--untracked-files=all",
+            "--",
+            ".",
+        ],
+    ) else {
+        return Ok(None);
+    };
+
+    let mut included = Vec::new();
+    walk(root, cfg, |path, _| {
+        included.push(path.to_owned());
+        Ok(())
+    })?;
+
+    let mut changed = Vec::new();
+    let mut untracked = Vec::new();
+    let mut entries = status.split('\0');
+    while let Some(entry) = entries.next() {
+        let Some((code, path)) = entry.split_at_checked(3) else {
+            continue;
+        };
+        // renames and copies are followed by the original path
+        if code.starts_with(['R', 'C']) {
+            entries.next();
+        }
+
+        let path = PathBuf::from(path.strip_prefix(prefix).unwrap_or(path));
+        if code == "?? " {
+            if included.contains(&path) {
+                untracked.push(path);
+            }
+        } else if code != "!! " {
+            changed.push(path);
+        }
+    }
+
+    Ok(Some(GitStatus {
+        commit,
+        changed,
+        untracked,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, process::Command};
+
+    use tempfile::tempdir;
+
+    use super::{export_rev, git_status};
+    use crate::config::Config;
+
+    fn git(dir: &std::path::Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.name=sagoin",
+                "-c",
+                "user.email=sagoin@localhost",
+            ])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+    }
+
+    #[test]
+    fn status() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("project");
+        fs::create_dir_all(&root).unwrap();
+        assert!(git_status(&root, &Config::default()).unwrap().is_none());
+
+        git(dir.path(), &["init", "-q"]);
+        fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(root.join("a.txt"), "foo").unwrap();
+        fs::write(dir.path().join("outside.txt"), "").unwrap();
+
+        let status = git_status(&root, &Config::default()).unwrap().unwrap();
+        assert_eq!(status.commit, None);
+
+        git(dir.path(), &["add", "project"]);
+        git(dir.path(), &["commit", "-qm", "init"]);
+        fs::write(root.join("a.txt"), "bar").unwrap();
+        fs::write(root.join("b.txt"), "").unwrap();
+        fs::write(root.join("build.log"), "").unwrap();
+
+        let status = git_status(&root, &Config::default()).unwrap().unwrap();
+        assert_eq!(status.commit.map(|commit| commit.len()), Some(40));
+        assert_eq!(status.changed, [PathBuf::from("a.txt")]);
+        assert_eq!(status.untracked, [PathBuf::from("b.txt")]);
+    }
+
+    #[test]
+    fn rev() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("project");
+        fs::create_dir_all(&root).unwrap();
+        git(dir.path(), &["init", "-q"]);
+        fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(root.join("a.txt"), "foo").unwrap();
+        fs::write(root.join("build.log"), "").unwrap();
+        fs::write(dir.path().join("outside.txt"), "").unwrap();
+        git(dir.path(), &["add", "-f", "."]);
+        git(dir.path(), &["commit", "-qm", "init"]);
+        git(dir.path(), &["tag", "v1"]);
+
+        fs::write(root.join("a.txt"), "bar").unwrap();
+        fs::write(root.join("b.txt"), "").unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-qm", "update"]);
+
+        let (tree, commit) = export_rev(&root, "v1").unwrap();
+        assert_eq!(commit.len(), 40);
+        assert_eq!(
+            fs::read_to_string(tree.path().join("a.txt")).unwrap(),
+            "foo"
+        );
+        assert!(!tree.path().join("b.txt").exists());
+        assert!(!tree.path().join("outside.txt").exists());
+
+        let mut paths = Vec::new();
+        crate::archive::walk(tree.path(), &Config::default(), |path, _| {
+            paths.push(path.to_owned());
+            Ok(())
+        })
+        .unwrap();
+        paths.sort();
+        assert_eq!(paths, [PathBuf::from(".gitignore"), PathBuf::from("a.txt")]);
+
+        assert!(export_rev(&root, "v2").is_err());
+    }
+
+    #[test]
+    fn rev_ignored() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("project");
+        fs::create_dir_all(root.join("src")).unwrap();
+        git(dir.path(), &["init", "-q"]);
+        fs::write(dir.path().join(".gitignore"), "*.o\n").unwrap();
+        fs::write(dir.path().join(".git/info/exclude"), "*.log\n").unwrap();
+        fs::write(dir.path().join(".gitattributes"), "*.txt export-ignore\n").unwrap();
+        fs::write(root.join("a.txt"), "foo").unwrap();
+        fs::write(root.join(".sagoin.toml"), "").unwrap();
+        fs::write(root.join("src/main.o"), "").unwrap();
+        fs::write(root.join("src/build.log"), "").unwrap();
+        fs::write(root.join("src/Main.java"), "").unwrap();
+        git(dir.path(), &["add", "-f", "."]);
+        git(dir.path(), &["commit", "-qm", "init"]);
+
+        let (tree, _) = export_rev(&root, "HEAD").unwrap();
+        let mut paths = Vec::new();
+        crate::archive::walk(tree.path(), &Config::default(), |path, _| {
+            paths.push(path.to_owned());
+            Ok(())
+        })
+        .unwrap();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [PathBuf::from("a.txt"), PathBuf::from("src/Main.java")],
+        );
+        assert!(tree.path().join(".sagoin.toml").exists());
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use eyre::{Result, WrapErr};
+use is_executable::IsExecutable;
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+
+use crate::{
+    archive::{entry_name, walk, Archive},
+    config::Config,
+    state::State,
+    warn, Props, PropsExt,
+};
+
+/// What was last submitted to a project
+pub(crate) struct History {
+    path: Option<PathBuf>,
+    props: Props,
+}
+
+impl History {
+    pub(crate) fn load(props: &Props) -> Result<Self> {
+        let name = history_key(props)?;
+        Ok(Self::at(state_dir().map(|dir| {
+            dir.join("history").join(format!("{name}.properties"))
+        })))
+    }
+
+    fn at(path: Option<PathBuf>) -> Self {
+        let props = path
+            .as_ref()
+            .and_then(|path| File::open(path).ok())
+            .and_then(|file| java_properties::read(file).ok())
+            .unwrap_or_default();
+        Self { path, props }
+    }
+
+    /// Content hash of the last submission
+    pub(crate) fn hash(&self) -> Option<&String> {
+        self.props.get("hash")
+    }
+
+    fn store(&mut self, hash: String, commit: Option<&str>) -> Result<()> {
+        self.props.insert("hash".into(), hash);
+        if let Some(commit) = commit {
+            self.props.insert("commit".into(), commit.into());
+        } else {
+            self.props.remove("commit");
+        }
+        self.props.insert(
+            "submitted".into(),
+            OffsetDateTime::now_utc().unix_timestamp().to_string(),
+        );
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).wrap_err("failed to create the history directory")?;
+        }
+        java_properties::write(
+            File::create(path)
+                .wrap_err_with(|| format!("failed to write to {}", path.display()))?,
+            &self.props,
+        )
+        .wrap_err_with(|| format!("failed to write to {}", path.display()))
+    }
+}
+
+impl<W: Write> State<W> {
+    pub(crate) fn record_submission(
+        &mut self,
+        history: &mut History,
+        hash: String,
+        commit: Option<&str>,
+    ) {
+        if let Err(e) = history.store(hash, commit) {
+            warn!(self, "failed to record the submission: {e:?}");
+        }
+    }
+}
+
+/// Hashes the paths, contents, and executable bits of the files that would be submitted
+pub(crate) fn content_hash(root: &Path, cfg: &Config) -> Result<String> {
+    let mut paths = Vec::new();
+    walk(root, cfg, |path, link| {
+        paths.push((path.to_owned(), link.map(Path::to_owned)));
+        Ok(())
+    })?;
+    paths.sort();
+
+    let mut hasher = Sha256::new();
+    for (path, link) in paths {
+        let full = root.join(&path);
+        let (executable, contents) = if let Some(link) = link {
+            (false, link.to_string_lossy().into_owned().into_bytes())
+        } else {
+            (
+                full.is_executable(),
+                fs::read(&full).wrap_err_with(|| format!("failed to read {}", path.display()))?,
+            )
+        };
+
+        let path = entry_name(&path);
+        hasher.update((path.len() as u64).to_le_bytes());
+        hasher.update(path.as_bytes());
+        hasher.update([executable as u8]);
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(contents);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Hashes the bytes of a prebuilt zip file
+pub(crate) fn archive_hash(zip: &Archive) -> Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut zip.reader()?, &mut hasher).wrap_err("failed to read the zip file")?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Hashes the server, course, and project of the submission,
+/// so different projects never share a history file
+fn history_key(props: &Props) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for key in ["baseURL", "courseKey", "projectNumber"] {
+        // the length keeps the fields from running into each other
+        let value = props.get_prop(key)?;
+        hasher.update((value.len() as u64).to_le_bytes());
+        hasher.update(value);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(unix)]
+fn state_dir() -> Option<PathBuf> {
+    xdg::BaseDirectories::with_prefix("sagoin")
+        .ok()
+        .map(|dirs| dirs.get_state_home())
+}
+
+#[cfg(windows)]
+fn state_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("sagoin"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::{content_hash, history_key, History};
+    use crate::config::Config;
+
+    #[test]
+    fn key() {
+        let key = |base_url: &str, course_key: &str, project_number: &str| {
+            history_key(
+                &[
+                    ("baseURL", base_url),
+                    ("courseKey", course_key),
+                    ("projectNumber", project_number),
+                ]
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(
+            key("https://a.edu", "a-b", "1"),
+            key("https://a.edu", "a-b", "1")
+        );
+        assert_ne!(
+            key("https://a.edu", "a-b", "1"),
+            key("https://a.edu", "a_b", "1")
+        );
+        assert_ne!(
+            key("https://a.edu", "a-b", "1"),
+            key("https://a.edu", "a", "b-1")
+        );
+        assert_ne!(
+            key("https://a.edu", "ab", "1"),
+            key("https://a.edu", "a", "b1")
+        );
+    }
+
+    #[test]
+    fn hash() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "foo").unwrap();
+
+        let before = content_hash(dir.path(), &Config::default()).unwrap();
+        assert_eq!(
+            before,
+            content_hash(dir.path(), &Config::default()).unwrap()
+        );
+
+        fs::write(dir.path().join(".submitUser"), "").unwrap();
+        assert_eq!(
+            before,
+            content_hash(dir.path(), &Config::default()).unwrap()
+        );
+
+        fs::write(dir.path().join("a.txt"), "bar").unwrap();
+        assert_ne!(
+            before,
+            content_hash(dir.path(), &Config::default()).unwrap()
+        );
+
+        fs::write(dir.path().join("a.txt"), "foo").unwrap();
+        assert_eq!(
+            before,
+            content_hash(dir.path(), &Config::default()).unwrap()
+        );
+
+        fs::rename(dir.path().join("a.txt"), dir.path().join("b.txt")).unwrap();
+        assert_ne!(
+            before,
+            content_hash(dir.path(), &Config::default()).unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hash_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("run.sh");
+        fs::write(&path, "").unwrap();
+
+        let before = content_hash(dir.path(), &Config::default()).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        assert_ne!(
+            before,
+            content_hash(dir.path(), &Config::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn store() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("history").join("project.properties");
+
+        let mut history = History::at(Some(path.clone()));
+        assert_eq!(history.hash(), None);
+
+        history.store("abc".into(), Some("0123abc")).unwrap();
+        assert_eq!(
+            History::at(Some(path)).hash().map(String::as_str),
+            Some("abc")
+        );
+    }
+}
@@ -11,14 +11,31 @@
 
 #![forbid(unsafe_code)]
 
+pub mod archive;
 mod auth;
+mod batch;
+mod cache;
+mod classify;
 mod cli;
 mod cmd;
 pub mod config;
 pub mod course;
 mod cred;
+pub mod discover;
+mod exclude;
+mod export;
+mod git;
+mod history;
+mod list;
+mod open;
+mod progress;
+mod required;
+mod secrets;
 pub mod state;
 mod submit;
+mod text;
+pub mod validate;
+mod watch;
 
 use std::collections::HashMap;
 
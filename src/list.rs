@@ -0,0 +1,186 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{stdout, Write},
+    path::{Path, PathBuf},
+};
+
+use eyre::{Result, WrapErr};
+use is_executable::IsExecutable;
+
+use crate::{
+    archive::{walk_entries, Archive, Entry},
+    classify::classify,
+    config::Config,
+    progress::format_bytes,
+    state::State,
+    Props,
+};
+
+struct Listed {
+    path: PathBuf,
+    link: Option<PathBuf>,
+    annotation: Option<String>,
+    size: u64,
+}
+
+#[derive(Default)]
+struct Tree {
+    children: BTreeMap<String, Tree>,
+    listed: Option<usize>,
+}
+
+impl<W: Write> State<W> {
+    /// Lists the files that would be submitted to stdout,
+    /// warning about the files that might not be meant to be submitted
+    pub fn list_files(&mut self, root: &Path, props: &Props, cfg: &Config) -> Result<()> {
+        self.check_symlinks(root, cfg)?;
+        self.scan_secrets(root, cfg)?;
+        self.check_binaries(root, props, cfg)?;
+        self.check_required_files(root, props, cfg)?;
+
+        let mut listed = Vec::new();
+        let mut excluded = Vec::new();
+        walk_entries(root, cfg, |path, entry| {
+            let link = match entry {
+                Entry::File(link) => link,
+                Entry::Excluded(reason) => {
+                    if cfg.verbose {
+                        excluded.push((path.to_owned(), reason.to_string()));
+                    }
+                    return Ok(());
+                }
+            };
+
+            let (annotation, size) = if link.is_some() {
+                (cfg.annotate.then(|| "symlink".into()), 0)
+            } else if cfg.annotate {
+                let (class, size) = classify(root, path)?;
+                let executable = if root.join(path).is_executable() {
+                    "x"
+                } else {
+                    "-"
+                };
+                (
+                    Some(format!("{class}, {executable}, {}", format_bytes(size))),
+                    size,
+                )
+            } else {
+                let size = fs::metadata(root.join(path))
+                    .wrap_err_with(|| format!("failed to read metadata of {}", path.display()))?
+                    .len();
+                (None, size)
+            };
+
+            listed.push(Listed {
+                path: path.to_owned(),
+                link: link.map(Path::to_owned),
+                annotation,
+                size,
+            });
+
+            Ok(())
+        })?;
+        listed.sort_by(|x, y| x.path.cmp(&y.path));
+
+        let mut out = stdout().lock();
+        if cfg.tree {
+            print_tree(&mut out, &listed)?;
+        } else {
+            for file in &listed {
+                if let Some(annotation) = &file.annotation {
+                    write!(out, "{annotation:<32}  ")?;
+                }
+                print_path(&mut out, file, file.path.display())?;
+            }
+        }
+
+        if cfg.totals {
+            write!(
+                out,
+                "{} files, {} uncompressed",
+                listed.len(),
+                format_bytes(listed.iter().map(|file| file.size).sum()),
+            )?;
+            if cfg.compressed_size {
+                write!(
+                    out,
+                    ", {} compressed",
+                    format_bytes(Archive::build(root, cfg)?.size()?),
+                )?;
+            }
+            writeln!(out)?;
+        }
+
+        if cfg.verbose {
+            excluded.sort();
+            if !excluded.is_empty() {
+                writeln!(out, "Excluded:")?;
+            }
+            for (path, reason) in excluded {
+                let slash = if root.join(&path).is_dir() { "/" } else { "" };
+                writeln!(out, "  {}{slash} ({reason})", path.display())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn print_path(out: &mut impl Write, file: &Listed, name: impl std::fmt::Display) -> Result<()> {
+    if let Some(link) = &file.link {
+        writeln!(out, "{name} -> {}", link.display())?;
+    } else {
+        writeln!(out, "{name}")?;
+    }
+    Ok(())
+}
+
+fn print_tree(out: &mut impl Write, listed: &[Listed]) -> Result<()> {
+    let mut tree = Tree::default();
+    for (i, file) in listed.iter().enumerate() {
+        let mut node = &mut tree;
+        for component in file.path.iter() {
+            node = node
+                .children
+                .entry(component.to_string_lossy().into_owned())
+                .or_default();
+        }
+        node.listed = Some(i);
+    }
+
+    writeln!(out, ".")?;
+    print_children(out, listed, &tree, "")
+}
+
+fn print_children(
+    out: &mut impl Write,
+    listed: &[Listed],
+    tree: &Tree,
+    prefix: &str,
+) -> Result<()> {
+    let mut children = tree.children.iter().peekable();
+    while let Some((name, child)) = children.next() {
+        let last = children.peek().is_none();
+        write!(out, "{prefix}{}", if last { "└── " } else { "├── " })?;
+
+        if let Some(file) = child.listed.map(|i| &listed[i]) {
+            let name = match &file.annotation {
+                Some(annotation) => format!("{name}  ({annotation})"),
+                None => name.clone(),
+            };
+            print_path(out, file, name)?;
+        } else {
+            writeln!(out, "{name}/")?;
+        }
+
+        print_children(
+            out,
+            listed,
+            child,
+            &format!("{prefix}{}", if last { "    " } else { "│   " }),
+        )?;
+    }
+
+    Ok(())
+}
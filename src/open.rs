@@ -0,0 +1,61 @@
+use std::io::{stdout, Write};
+
+use eyre::{Result, WrapErr};
+
+use crate::{cli::OpenTarget, config::Config, state::State, Props, PropsExt};
+
+impl<W: Write> State<W> {
+    /// Opens the page specified by --open, or prints its URL with --print-url
+    pub fn open(&mut self, props: &Props, cfg: &Config) -> Result<()> {
+        let Some(target) = cfg.open else {
+            return Ok(());
+        };
+
+        let url = self.target_url(props, cfg, target)?;
+        if cfg.print_url {
+            writeln!(stdout().lock(), "{url}")?;
+        } else {
+            webbrowser::open(&url).wrap_err("failed to open the web browser")?;
+        }
+
+        Ok(())
+    }
+
+    fn target_url(&mut self, props: &Props, cfg: &Config, target: OpenTarget) -> Result<String> {
+        let base_url = props.get_prop("baseURL")?.trim_end_matches('/');
+
+        Ok(match target {
+            OpenTarget::Project => return self.get_course_url(props, cfg),
+            OpenTarget::Server => base_url.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cli::OpenTarget, config::Config, state::State, Props};
+
+    fn props() -> Props {
+        [
+            ("baseURL", "https://submit.cs.umd.edu/fall2022/"),
+            ("courseKey", "abc"),
+            ("projectNumber", "1"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.into(), v.into()))
+        .collect()
+    }
+
+    #[test]
+    fn target_urls() {
+        let mut state = State::sink();
+        let cfg = Config::default();
+
+        assert_eq!(
+            state
+                .target_url(&props(), &cfg, OpenTarget::Server)
+                .unwrap(),
+            "https://submit.cs.umd.edu/fall2022",
+        );
+    }
+}
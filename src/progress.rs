@@ -0,0 +1,153 @@
+use std::{
+    io::{self, Read, Write},
+    time::Instant,
+};
+
+/// Reports how much of the inner reader has been read
+pub(crate) struct Progress<'a, R, W> {
+    inner: R,
+    out: &'a mut W,
+    sent: u64,
+    total: u64,
+    start: Instant,
+    shown: Option<u64>,
+    width: usize,
+}
+
+impl<'a, R: Read, W: Write> Progress<'a, R, W> {
+    pub(crate) fn new(inner: R, out: &'a mut W, total: u64) -> Self {
+        Self {
+            inner,
+            out,
+            sent: 0,
+            total,
+            start: Instant::now(),
+            shown: None,
+            width: 0,
+        }
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let percent = (self.sent * 100).checked_div(self.total).unwrap_or(100);
+        if self.shown == Some(percent) {
+            return Ok(());
+        }
+        self.shown = Some(percent);
+
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let rate = if elapsed > 0.0 {
+            self.sent as f64 / elapsed
+        } else {
+            0.0
+        };
+
+        let line = if self.sent >= self.total {
+            format!(
+                "Uploaded {} in {}",
+                format_bytes(self.total),
+                format_secs(elapsed),
+            )
+        } else {
+            let mut line = format!(
+                "Uploading: {} / {} ({percent}%)",
+                format_bytes(self.sent),
+                format_bytes(self.total),
+            );
+            if rate > 0.0 {
+                line.push_str(&format!(
+                    ", {}/s, {} left",
+                    format_bytes(rate as u64),
+                    format_secs((self.total - self.sent) as f64 / rate),
+                ));
+            }
+            line
+        };
+
+        // pad with spaces to cover the previous line when it was longer
+        write!(self.out, "\r{line:width$}", width = self.width)?;
+        self.width = line.len();
+        if self.sent >= self.total {
+            writeln!(self.out)?;
+        }
+        self.out.flush()
+    }
+}
+
+impl<R: Read, W: Write> Read for Progress<'_, R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.sent += n as u64;
+        // failing to draw the progress should not fail the upload
+        let _ = self.draw();
+        Ok(n)
+    }
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1 ..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next;
+    }
+
+    format!("{size:.1} {unit}")
+}
+
+fn format_secs(secs: f64) -> String {
+    let secs = secs.ceil() as u64;
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read};
+
+    use super::{format_bytes, format_secs, Progress};
+
+    #[test]
+    fn bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn secs() {
+        assert_eq!(format_secs(0.0), "0s");
+        assert_eq!(format_secs(4.2), "5s");
+        assert_eq!(format_secs(65.0), "1m05s");
+        assert_eq!(format_secs(3700.0), "1h01m");
+    }
+
+    #[test]
+    fn progress() {
+        let data = vec![0; 4096];
+        let mut out = Vec::new();
+
+        let mut progress = Progress::new(data.as_slice(), &mut out, 4096);
+        io::copy(&mut progress, &mut io::sink()).unwrap();
+        assert_eq!(progress.read(&mut [0; 16]).unwrap(), 0);
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("\rUploaded 4.0 KiB in "));
+        assert!(out.ends_with("s\n"));
+        assert_eq!(out.matches('\n').count(), 1);
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use eyre::{Result, WrapErr};
+use globset::GlobBuilder;
+use serde::Deserialize;
+
+use crate::{archive::collect_files, config::Config, state::State, warn, Props};
+
+/// Name of the project-local config file, which is never submitted
+pub(crate) const PROJECT_CONFIG: &str = ".sagoin.toml";
+
+/// Settings that only apply to the project, read from .sagoin.toml next to .submit
+#[derive(Default, Deserialize)]
+struct ProjectConfig {
+    #[serde(default)]
+    required_files: Vec<String>,
+}
+
+impl<W: Write> State<W> {
+    /// Warns about required files that would not be submitted,
+    /// returning whether all of them are present
+    pub fn check_required_files(
+        &mut self,
+        root: &Path,
+        props: &Props,
+        cfg: &Config,
+    ) -> Result<bool> {
+        self.check_required_in(root, &collect_files(root, cfg)?, props, cfg)
+    }
+
+    /// Warns about required patterns that none of the files match,
+    /// returning whether all of them are present
+    pub(crate) fn check_required_in(
+        &mut self,
+        root: &Path,
+        files: &[(PathBuf, Option<PathBuf>)],
+        props: &Props,
+        cfg: &Config,
+    ) -> Result<bool> {
+        let missing = missing_files(root, files, props, cfg)?;
+        if missing.is_empty() {
+            return Ok(true);
+        }
+
+        warn!(self, "missing required files:");
+        for pattern in missing {
+            writeln!(self.out, "  {pattern}")?;
+        }
+
+        Ok(false)
+    }
+}
+
+/// Finds the required patterns that none of the files match,
+/// reading the project config from the root
+fn missing_files(
+    root: &Path,
+    files: &[(PathBuf, Option<PathBuf>)],
+    props: &Props,
+    cfg: &Config,
+) -> Result<Vec<String>> {
+    let path = root.join(PROJECT_CONFIG);
+    let project: ProjectConfig = match fs::read_to_string(&path) {
+        Ok(project) => toml::from_str(&project)
+            .wrap_err_with(|| format!("failed to parse {}", path.display()))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => ProjectConfig::default(),
+        Err(e) => return Err(e).wrap_err_with(|| format!("failed to read {}", path.display())),
+    };
+
+    let mut required = Vec::new();
+    for pattern in cfg
+        .course(props)
+        .map_or(&[][..], |course| &course.required_files)
+        .iter()
+        .chain(&project.required_files)
+    {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .wrap_err_with(|| format!("invalid pattern {pattern}"))?
+            .compile_matcher();
+        required.push((pattern, glob, false));
+    }
+
+    for (path, _) in files {
+        for (_, glob, found) in &mut required {
+            *found |= glob.is_match(path);
+        }
+    }
+
+    Ok(required
+        .into_iter()
+        .filter(|(_, _, found)| !found)
+        .map(|(pattern, ..)| pattern.clone())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use tempfile::tempdir;
+
+    use super::missing_files;
+    use crate::{
+        archive::collect_files,
+        config::{Config, CourseConfig},
+    };
+
+    fn files(root: &Path) -> Vec<(PathBuf, Option<PathBuf>)> {
+        collect_files(root, &Config::default()).unwrap()
+    }
+
+    #[test]
+    fn missing() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/util")).unwrap();
+        fs::write(dir.path().join("src/Main.java"), "").unwrap();
+        fs::write(dir.path().join("src/util/List.java"), "").unwrap();
+        fs::write(
+            dir.path().join(".sagoin.toml"),
+            r#"required_files = ["README*", "src/**/List.java"]"#,
+        )
+        .unwrap();
+
+        let props = [("courseName".into(), "CMSC132".into())].into();
+        let cfg = Config {
+            courses: HashMap::from([(
+                "CMSC132".into(),
+                CourseConfig {
+                    required_files: vec!["src/Main.java".into(), "*.java".into()],
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            missing_files(dir.path(), &files(dir.path()), &props, &cfg).unwrap(),
+            ["*.java", "README*"],
+        );
+
+        fs::write(dir.path().join("README.md"), "").unwrap();
+        fs::write(dir.path().join("Test.java"), "").unwrap();
+        assert!(missing_files(dir.path(), &files(dir.path()), &props, &cfg)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn unreadable_config() {
+        let dir = tempdir().unwrap();
+        assert!(missing_files(
+            dir.path(),
+            &files(dir.path()),
+            &Default::default(),
+            &Config::default()
+        )
+        .unwrap()
+        .is_empty());
+
+        fs::create_dir(dir.path().join(".sagoin.toml")).unwrap();
+        assert!(missing_files(
+            dir.path(),
+            &files(dir.path()),
+            &Default::default(),
+            &Config::default()
+        )
+        .is_err());
+    }
+}
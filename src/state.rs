@@ -1,6 +1,6 @@
 use std::{
     fmt::Display,
-    io::{self, StderrLock, Write},
+    io::{self, IsTerminal, StderrLock, Write},
 };
 
 use color_eyre::config::{HookBuilder, Theme};
@@ -8,6 +8,7 @@ use eyre::{Result, WrapErr};
 
 pub struct State<W: Write> {
     pub color: bool,
+    pub terminal: bool,
     pub out: W,
 }
 
@@ -36,6 +37,7 @@ impl State<StderrLock<'static>> {
 
         Ok(Self {
             color,
+            terminal: io::stderr().is_terminal(),
             out: io::stderr().lock(),
         })
     }
@@ -60,6 +62,7 @@ impl State<io::Sink> {
     pub(crate) fn sink() -> Self {
         Self {
             color: false,
+            terminal: false,
             out: io::sink(),
         }
     }
@@ -70,6 +73,7 @@ impl State<Vec<u8>> {
     pub(crate) fn buffer() -> Self {
         Self {
             color: false,
+            terminal: false,
             out: Vec::new(),
         }
     }
@@ -1,68 +1,202 @@
-use std::io::Write;
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
-use eyre::{eyre, Result, WrapErr};
+use eyre::{bail, eyre, Result, WrapErr};
 use multipart::client::lazy::Multipart;
 
-use crate::{config::Config, state::State, warn, Props, PropsExt};
+use crate::{
+    archive::{collect_files, Archive},
+    config::Config,
+    git::export_rev,
+    history::{archive_hash, content_hash, History},
+    progress::Progress,
+    required::PROJECT_CONFIG,
+    state::State,
+    warn, Props, PropsExt,
+};
 
 impl<W: Write> State<W> {
+    /// Submits the files under the root, or the files tracked at the revision if specified,
+    /// unless they are identical to the last submission
     pub fn submit(
         &mut self,
         user_props: Props,
         props: &Props,
         cfg: &Config,
-        zip: &[u8],
+        root: &Path,
     ) -> Result<()> {
+        if let Some(path) = &cfg.archive {
+            return self.submit_prebuilt(user_props, props, cfg, path);
+        }
+
+        let rev = cfg
+            .rev
+            .as_ref()
+            .map(|rev| export_rev(root, rev))
+            .transpose()?;
+        let root = rev.as_ref().map_or(root, |(dir, _)| dir.path());
+
+        let hash = content_hash(root, cfg)?;
+        let mut history = History::load(props)?;
+        if !cfg.force && history.hash() == Some(&hash) {
+            warn!(
+                self,
+                "nothing has changed since the last submission, use --force to submit anyway",
+            );
+            return Ok(());
+        }
+
+        self.check_symlinks(root, cfg)?;
+        self.check_binaries(root, props, cfg)?;
+        self.check_files(root, &collect_files(root, cfg)?, props, cfg)?;
+        let commit = if let Some((_, commit)) = &rev {
+            writeln!(self.out, "Commit: {commit}")?;
+            Some(commit.clone())
+        } else if cfg.check_git || cfg.commit_field.is_some() {
+            self.check_git(root, cfg)?
+        } else {
+            None
+        };
+        let zip = Archive::build(root, cfg)?;
         self.run_hook(&cfg.pre_submit_hook, "pre-submit")?;
-        self.submit_project(user_props, props, cfg, zip, true)?;
-        self.run_hook(&cfg.post_submit_hook, "post-submit")?;
+        self.submit_archive(user_props, props, cfg, &zip, commit.as_deref())?;
+        self.record_submission(&mut history, hash, commit.as_deref());
+
+        Ok(())
+    }
+
+    /// Submits a prebuilt zip file or tarball,
+    /// unless it is identical to the last submission
+    fn submit_prebuilt(
+        &mut self,
+        user_props: Props,
+        props: &Props,
+        cfg: &Config,
+        path: &Path,
+    ) -> Result<()> {
+        let zip = Archive::open(path, cfg)?;
+        let hash = archive_hash(&zip)?;
+        let mut history = History::load(props)?;
+        if !cfg.force && history.hash() == Some(&hash) {
+            warn!(
+                self,
+                "nothing has changed since the last submission, use --force to submit anyway",
+            );
+            return Ok(());
+        }
+
+        // the zip file is submitted as is, so every file in it is checked,
+        // using the project config next to .submit unless the archive has its own
+        let dir = zip.extract()?;
+        if !dir.path().join(PROJECT_CONFIG).exists() && Path::new(PROJECT_CONFIG).is_file() {
+            fs::copy(PROJECT_CONFIG, dir.path().join(PROJECT_CONFIG))
+                .wrap_err_with(|| format!("failed to read {PROJECT_CONFIG}"))?;
+        }
+        self.check_files(dir.path(), &zip.entries()?, props, cfg)?;
+
+        self.run_hook(&cfg.pre_submit_hook, "pre-submit")?;
+        self.submit_archive(user_props, props, cfg, &zip, None)?;
+        self.record_submission(&mut history, hash, None);
+
+        Ok(())
+    }
+
+    /// Refuses to submit when required files are missing or some files may contain secrets
+    fn check_files(
+        &mut self,
+        root: &Path,
+        files: &[(PathBuf, Option<PathBuf>)],
+        props: &Props,
+        cfg: &Config,
+    ) -> Result<()> {
+        if !self.check_required_in(root, files, props, cfg)? {
+            bail!("refusing to submit without the required files, did you submit the wrong directory?");
+        }
+        if self.scan_files_for_secrets(root, files, cfg)? && !cfg.allow_secrets {
+            bail!(
+                "refusing to submit files that may contain secrets, \
+                remove or ignore them, or use --allow-secrets to submit anyway"
+            );
+        }
 
         Ok(())
     }
 
+    /// Submits the zip file without running the pre-submit hook,
+    /// returning the credentials used for the submission
+    pub(crate) fn submit_archive(
+        &mut self,
+        user_props: Props,
+        props: &Props,
+        cfg: &Config,
+        zip: &Archive,
+        commit: Option<&str>,
+    ) -> Result<Props> {
+        let user_props = self.submit_project(user_props, props, cfg, zip, commit, true)?;
+        self.run_hook(&cfg.post_submit_hook, "post-submit")?;
+
+        Ok(user_props)
+    }
+
     fn submit_project(
         &mut self,
         user_props: Props,
         props: &Props,
         cfg: &Config,
-        zip: &[u8],
+        zip: &Archive,
+        commit: Option<&str>,
         reauth: bool,
-    ) -> Result<()> {
+    ) -> Result<Props> {
         if reauth
             && (!user_props.contains_key("cvsAccount") && !user_props.contains_key("classAccount")
                 || !user_props.contains_key("oneTimePassword"))
         {
             let user_props = self.negotiate_otp(props, cfg)?;
-            return self.submit_project(user_props, props, cfg, zip, false);
+            return self.submit_project(user_props, props, cfg, zip, commit, false);
         }
 
-        let mut parts = Multipart::new();
-
-        for (k, v) in user_props.iter().chain(props) {
-            parts.add_text(k.clone(), v);
+        let total = zip.size()?;
+        let mut stream = zip.reader()?;
+        if self.terminal && !cfg.quiet {
+            stream = Box::new(Progress::new(stream, &mut self.out, total));
         }
 
-        parts
-            .add_text("submitClientTool", &cfg.client_name)
-            .add_text("submitClientVersion", &cfg.client_version);
+        let parts = {
+            let mut parts = Multipart::new();
 
-        let mut fields = cfg.fields.iter();
-        while let (Some(k), Some(v)) = (fields.next(), fields.next()) {
-            parts.add_text(k.clone(), v);
-        }
+            for (k, v) in user_props.iter().chain(props) {
+                parts.add_text(k.clone(), v.clone());
+            }
 
-        let parts = parts
-            .add_stream(
-                "submittedFiles",
-                zip,
-                Some("submit.zip"),
-                Some(
-                    "application/zip"
-                        .parse()
-                        .wrap_err("failed to parse application/zip as a mime type")?,
-                ),
-            )
-            .prepare()?;
+            parts
+                .add_text("submitClientTool", &cfg.client_name)
+                .add_text("submitClientVersion", &cfg.client_version);
+
+            let mut fields = cfg.fields.iter();
+            while let (Some(k), Some(v)) = (fields.next(), fields.next()) {
+                parts.add_text(k.clone(), v);
+            }
+
+            if let (Some(k), Some(commit)) = (&cfg.commit_field, commit) {
+                parts.add_text(k.clone(), commit.to_owned());
+            }
+
+            parts
+                .add_stream(
+                    "submittedFiles",
+                    stream,
+                    Some("submit.zip"),
+                    Some(
+                        "application/zip"
+                            .parse()
+                            .wrap_err("failed to parse application/zip as a mime type")?,
+                    ),
+                )
+                .prepare()?
+        };
 
         match ureq::post(props.get_prop("submitURL")?)
             .set(
@@ -78,7 +212,7 @@ impl<W: Write> State<W> {
                     writeln!(self.out, "Successful submission received")?;
                 }
 
-                Ok(())
+                Ok(user_props)
             }
 
             Err(ureq::Error::Status(500, resp)) if reauth => {
@@ -87,7 +221,7 @@ impl<W: Write> State<W> {
                     warn!(self, "{err}");
                 }
                 let user_props = self.negotiate_otp(props, cfg)?;
-                self.submit_project(user_props, props, cfg, zip, false)
+                self.submit_project(user_props, props, cfg, zip, commit, false)
             }
 
             Err(ureq::Error::Status(code, resp)) => Err(if let Ok(err) = resp.into_string() {
@@ -0,0 +1,211 @@
+/// Stands in for backslashes so the iCalendar parser doesn't unescape text on its own,
+/// which it does lossily (https://github.com/hoodie/icalendar-rs/issues/53)
+pub(crate) const BACKSLASH: char = '\u{e000}';
+
+/// Hides backslashes in the calendar from the iCalendar parser
+pub(crate) fn hide_backslashes(input: &str) -> String {
+    input.replace('\\', BACKSLASH.encode_utf8(&mut [0; 3]))
+}
+
+/// Restores backslashes in values that are not TEXT
+pub(crate) fn restore_backslashes(input: &str) -> String {
+    input.replace(BACKSLASH, "\\")
+}
+
+/// Unescapes TEXT values as specified in RFC 5545 section 3.3.11
+pub(crate) fn unescape(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' && c != BACKSLASH {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => output.push('\n'),
+            Some('\\' | BACKSLASH) => output.push('\\'),
+            Some(c @ (',' | ';' | ':')) => output.push(c),
+            Some(c) => {
+                output.push('\\');
+                output.push(c);
+            }
+            None => output.push('\\'),
+        }
+    }
+
+    output
+}
+
+/// Converts HTML to plain text, returning the input as is if it does not look like HTML
+pub(crate) fn html_to_text(input: &str) -> String {
+    if !is_html(input) {
+        return input.into();
+    }
+
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(i) = rest.find('<') {
+        push_text(&mut output, &rest[.. i]);
+        rest = &rest[i ..];
+
+        let Some(j) = rest.find('>') else {
+            break;
+        };
+        let tag = rest[1 .. j].trim().to_ascii_lowercase();
+        rest = &rest[j + 1 ..];
+
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+
+        match name {
+            "br" => output.push('\n'),
+            "li" if !tag.starts_with('/') => {
+                newline(&mut output);
+                output.push_str("- ");
+            }
+            "p" | "div" | "ul" | "ol" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                newline(&mut output);
+            }
+            "td" | "th" if tag.starts_with('/') => output.push(' '),
+            _ => {}
+        }
+    }
+    push_text(&mut output, rest);
+
+    let mut text = String::with_capacity(output.len());
+    let mut blank = false;
+    for line in output.lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank = !text.is_empty();
+        } else {
+            if blank {
+                text.push('\n');
+                blank = false;
+            }
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    text.truncate(text.trim_end().len());
+
+    text
+}
+
+fn is_html(input: &str) -> bool {
+    input.match_indices('<').any(|(i, _)| {
+        matches!(input[i + 1 ..].chars().next(), Some(c) if c.is_ascii_alphabetic() || c == '/')
+            && input[i ..].contains('>')
+    })
+}
+
+fn newline(output: &mut String) {
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+}
+
+/// Pushes text with collapsed whitespace and decoded character references
+fn push_text(output: &mut String, text: &str) {
+    let mut rest = text;
+    let mut space = false;
+
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            space = true;
+            rest = &rest[c.len_utf8() ..];
+            continue;
+        }
+
+        if space {
+            if !output.is_empty() && !output.ends_with(['\n', ' ']) {
+                output.push(' ');
+            }
+            space = false;
+        }
+
+        if c == '&' {
+            if let Some((decoded, len)) = decode_entity(rest) {
+                output.push(decoded);
+                rest = &rest[len ..];
+                continue;
+            }
+        }
+
+        output.push(c);
+        rest = &rest[c.len_utf8() ..];
+    }
+
+    if space && !output.is_empty() && !output.ends_with(['\n', ' ']) {
+        output.push(' ');
+    }
+}
+
+fn decode_entity(input: &str) -> Option<(char, usize)> {
+    let end = input.find(';').filter(|&end| end <= 10)?;
+    let name = &input[1 .. end];
+
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        _ => char::from_u32(
+            if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                u32::from_str_radix(hex, 16).ok()?
+            } else {
+                name.strip_prefix('#')?.parse().ok()?
+            },
+        )?,
+    };
+
+    Some((c, end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hide_backslashes, html_to_text, restore_backslashes, unescape};
+
+    #[test]
+    fn backslashes() {
+        let input = r"foo\, bar\\n";
+        assert!(!hide_backslashes(input).contains('\\'));
+        assert_eq!(restore_backslashes(&hide_backslashes(input)), input);
+        assert_eq!(unescape(&hide_backslashes(input)), r"foo, bar\n");
+    }
+
+    #[test]
+    fn unescape_text() {
+        assert_eq!(unescape(r"foo\, bar\; baz"), "foo, bar; baz");
+        assert_eq!(unescape(r"foo\nbar\Nbaz"), "foo\nbar\nbaz");
+        assert_eq!(unescape(r"C:\\new"), r"C:\new");
+        assert_eq!(unescape(r"\\n"), r"\n");
+        assert_eq!(unescape(r"foo\x"), r"foo\x");
+        assert_eq!(unescape("foo\\"), "foo\\");
+    }
+
+    #[test]
+    fn html_plain() {
+        assert_eq!(html_to_text("1 < 2 && 3 > 2"), "1 < 2 && 3 > 2");
+        assert_eq!(html_to_text("foo\n\nbar"), "foo\n\nbar");
+    }
+
+    #[test]
+    fn html_markup() {
+        assert_eq!(
+            html_to_text("<p>Hello <b>world</b></p><p>a&amp;b &lt;c&gt; &#39;d&#x27;</p>"),
+            "Hello world\na&b <c> 'd'",
+        );
+        assert_eq!(
+            html_to_text("<ul>\n  <li>foo</li>\n  <li>bar<br/>baz</li>\n</ul>"),
+            "- foo\n- bar\nbaz",
+        );
+    }
+}
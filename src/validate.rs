@@ -0,0 +1,205 @@
+use std::{
+    io::{stdout, Write},
+    path::Path,
+};
+
+use eyre::{bail, Result};
+use url::{Host, Url};
+
+use crate::{state::State, warn, Props};
+
+const REQUIRED: [&str; 6] = [
+    "baseURL",
+    "submitURL",
+    "courseKey",
+    "projectNumber",
+    "courseName",
+    "authentication.type",
+];
+
+const LABELS: [(&str, &str); 6] = [
+    ("courseName", "Course"),
+    ("courseKey", "Course key"),
+    ("projectNumber", "Project"),
+    ("baseURL", "Server"),
+    ("submitURL", "Submit URL"),
+    ("authentication.type", "Authentication"),
+];
+
+impl<W: Write> State<W> {
+    /// Prints the properties of the .submit file in a readable form
+    pub fn print_submit_file(&mut self, props: &Props) -> Result<()> {
+        let mut out = stdout().lock();
+
+        for (key, label) in LABELS {
+            writeln!(
+                out,
+                "{label}: {}",
+                props.get(key).map_or("(missing)", String::as_str),
+            )?;
+        }
+
+        let mut others: Vec<_> = props
+            .iter()
+            .filter(|(key, _)| !REQUIRED.contains(&key.as_str()))
+            .collect();
+        if !others.is_empty() {
+            others.sort();
+            writeln!(out, "Other properties:")?;
+            for (key, value) in others {
+                writeln!(out, "  {key} = {value}")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the properties of the .submit file, reporting every problem at once
+    /// and warning about the ones that don't stop it from working
+    pub fn validate_submit_file(&mut self, path: &Path, props: &Props) -> Result<()> {
+        let (problems, warnings) = find_problems(props);
+        for warning in warnings {
+            warn!(self, "{warning}");
+        }
+
+        if !problems.is_empty() {
+            bail!(
+                "{} is invalid:\n{}",
+                path.display(),
+                problems
+                    .iter()
+                    .map(|problem| format!("  {problem}"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the problems that make the .submit file unusable, and the ones that only need a warning
+fn find_problems(props: &Props) -> (Vec<String>, Vec<String>) {
+    let mut problems = Vec::new();
+    let mut warnings = Vec::new();
+
+    for key in REQUIRED {
+        if props.get(key).is_none_or(|value| value.trim().is_empty()) {
+            problems.push(format!("{key} is missing"));
+        }
+    }
+
+    for key in ["baseURL", "submitURL"] {
+        let Some(value) = props.get(key).filter(|value| !value.trim().is_empty()) else {
+            continue;
+        };
+
+        match Url::parse(value) {
+            Ok(url) => match url.scheme() {
+                "https" => {}
+                // plain http is fine when testing against a local server
+                "http" if is_local(&url) => {}
+                "http" => warnings.push(format!(
+                    "{key} uses http instead of https, which would send credentials in plain text",
+                )),
+                scheme => problems.push(format!("{key} has unsupported scheme {scheme}")),
+            },
+            Err(e) => problems.push(format!("{key} is not a valid URL: {e}")),
+        }
+    }
+
+    if let Some(ty) = props.get("authentication.type") {
+        if !ty.trim().is_empty() && !matches!(ty.as_str(), "cas" | "ldap") {
+            problems.push(format!("unsupported authentication type: {ty}"));
+        }
+    }
+
+    (problems, warnings)
+}
+
+fn is_local(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Domain(domain)) => domain == "localhost",
+        Some(Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{state::State, Props};
+
+    fn props(pairs: &[(&str, &str)]) -> Props {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn valid() {
+        let props = props(&[
+            ("baseURL", "https://submit.cs.umd.edu/fall2024"),
+            (
+                "submitURL",
+                "https://submit.cs.umd.edu/fall2024/eclipse/SubmitProjectViaEclipse",
+            ),
+            ("courseKey", "abc"),
+            ("projectNumber", "1"),
+            ("courseName", "CMSC132"),
+            ("authentication.type", "ldap"),
+        ]);
+        assert!(State::sink()
+            .validate_submit_file(Path::new(".submit"), &props)
+            .is_ok());
+    }
+
+    #[test]
+    fn local() {
+        let props = props(&[
+            ("baseURL", "http://localhost:8080"),
+            (
+                "submitURL",
+                "http://127.0.0.1:8080/eclipse/SubmitProjectViaEclipse",
+            ),
+            ("courseKey", "abc"),
+            ("projectNumber", "1"),
+            ("courseName", "CMSC132"),
+            ("authentication.type", "cas"),
+        ]);
+        assert!(State::sink()
+            .validate_submit_file(Path::new(".submit"), &props)
+            .is_ok());
+    }
+
+    #[test]
+    fn every_problem() {
+        let props = props(&[
+            ("baseURL", "http://submit.cs.umd.edu"),
+            ("submitURL", "not a url"),
+            ("courseKey", ""),
+            ("authentication.type", "oauth"),
+        ]);
+        let mut state = State::buffer();
+        let e = state
+            .validate_submit_file(Path::new(".submit"), &props)
+            .unwrap_err()
+            .to_string();
+        let warnings = String::from_utf8(state.out).unwrap();
+        assert!(warnings.contains("baseURL uses http instead of https"));
+        assert!(!e.contains("baseURL"), "{e}");
+
+        for problem in [
+            "courseKey is missing",
+            "projectNumber is missing",
+            "courseName is missing",
+            "submitURL is not a valid URL",
+            "unsupported authentication type: oauth",
+        ] {
+            assert!(e.contains(problem), "{e}");
+        }
+    }
+}
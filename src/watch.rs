@@ -0,0 +1,213 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fs,
+    hash::{Hash, Hasher},
+    io::Write,
+    path::Path,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use eyre::{Result, WrapErr};
+use is_executable::IsExecutable;
+
+use crate::{
+    archive::{walk, Archive},
+    config::Config,
+    history::{content_hash, History},
+    state::State,
+    warn, Props,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+impl<W: Write> State<W> {
+    /// Re-submits the project whenever the files that would be submitted change
+    pub fn watch(&mut self, mut user_props: Props, props: &Props, cfg: &Config) -> Result<()> {
+        let root = Path::new(".");
+        let debounce = Duration::try_from(cfg.debounce).unwrap_or_default();
+        let interval = Duration::try_from(cfg.watch_interval).unwrap_or_default();
+
+        self.check_symlinks(root, cfg)?;
+        self.check_binaries(root, props, cfg)?;
+        let mut snapshot = fingerprint(root, cfg)?;
+        let mut history = History::load(props)?;
+        let mut last_hash = if cfg.force {
+            None
+        } else {
+            history.hash().cloned()
+        };
+        let mut last_submit: Option<Instant> = None;
+
+        writeln!(self.out, "Watching for changes, press Ctrl-C to stop")?;
+
+        // errors inside of the loop are usually short-lived,
+        // e.g. a file disappearing while an editor saves it, so they don't stop watching
+        loop {
+            sleep(POLL_INTERVAL);
+            match fingerprint(root, cfg) {
+                Ok(current) if current == snapshot => continue,
+                Ok(current) => snapshot = current,
+                Err(e) => {
+                    warn!(self, "{e:#}");
+                    continue;
+                }
+            }
+
+            let mut stable_since = Instant::now();
+            while stable_since.elapsed() < debounce {
+                sleep(POLL_INTERVAL);
+                match fingerprint(root, cfg) {
+                    Ok(current) if current == snapshot => {}
+                    Ok(current) => {
+                        snapshot = current;
+                        stable_since = Instant::now();
+                    }
+                    Err(e) => {
+                        warn!(self, "{e:#}");
+                        stable_since = Instant::now();
+                    }
+                }
+            }
+
+            if let Some(last_submit) = last_submit {
+                let wait = interval.saturating_sub(last_submit.elapsed());
+                if !wait.is_zero() {
+                    writeln!(
+                        self.out,
+                        "Waiting {}s before submitting again",
+                        wait.as_secs_f64().ceil(),
+                    )?;
+                    sleep(wait);
+                }
+            }
+
+            if let Err(e) = self.run_hook(&cfg.pre_submit_hook, "pre-submit") {
+                warn!(self, "{e:#}, skipping submission");
+                continue;
+            }
+
+            // the hook may have changed the files, e.g. by formatting them
+            if let Ok(current) = fingerprint(root, cfg) {
+                snapshot = current;
+            }
+
+            let (hash, commit, zip) =
+                match self.prepare_submission(root, props, cfg, last_hash.as_ref()) {
+                    Ok(Some(submission)) => submission,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        warn!(self, "{e:#}, skipping submission");
+                        continue;
+                    }
+                };
+
+            match self.submit_archive(user_props.clone(), props, cfg, &zip, commit.as_deref()) {
+                Ok(props) => {
+                    user_props = props;
+                    last_hash = Some(hash.clone());
+                    last_submit = Some(Instant::now());
+                    self.record_submission(&mut history, hash, commit.as_deref());
+                }
+                Err(e) => warn!(self, "{e:#}"),
+            }
+        }
+    }
+
+    /// Checks the files and builds the submission zip,
+    /// returning None if the submission should be skipped
+    fn prepare_submission(
+        &mut self,
+        root: &Path,
+        props: &Props,
+        cfg: &Config,
+        last_hash: Option<&String>,
+    ) -> Result<Option<(String, Option<String>, Archive)>> {
+        if !self.check_required_files(root, props, cfg)? {
+            warn!(
+                self,
+                "skipping submission because some required files are missing"
+            );
+            return Ok(None);
+        }
+
+        if self.scan_secrets(root, cfg)? && !cfg.allow_secrets {
+            warn!(
+                self,
+                "skipping submission because some files may contain secrets, \
+                remove or ignore them, or use --allow-secrets to submit anyway",
+            );
+            return Ok(None);
+        }
+
+        let hash = content_hash(root, cfg)?;
+        if last_hash == Some(&hash) {
+            writeln!(self.out, "No changes to submit")?;
+            return Ok(None);
+        }
+
+        let commit = if cfg.check_git || cfg.commit_field.is_some() {
+            self.check_git(root, cfg)?
+        } else {
+            None
+        };
+
+        Ok(Some((hash, commit, Archive::build(root, cfg)?)))
+    }
+}
+
+/// Hashes the paths, sizes, modification times, and permissions
+/// of the files that would be submitted
+fn fingerprint(root: &Path, cfg: &Config) -> Result<u64> {
+    let mut hasher = DefaultHasher::new();
+
+    walk(root, cfg, |path, link| {
+        let full = root.join(path);
+        let meta = if link.is_some() {
+            fs::symlink_metadata(&full)
+        } else {
+            fs::metadata(&full)
+        }
+        .wrap_err_with(|| format!("failed to read metadata of {}", path.display()))?;
+
+        path.hash(&mut hasher);
+        link.hash(&mut hasher);
+        meta.len().hash(&mut hasher);
+        meta.modified().ok().hash(&mut hasher);
+        full.is_executable().hash(&mut hasher);
+
+        Ok(())
+    })?;
+
+    Ok(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::fingerprint;
+    use crate::config::Config;
+
+    #[test]
+    fn fingerprint_changes() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "foo").unwrap();
+
+        let before = fingerprint(dir.path(), &Config::default()).unwrap();
+        assert_eq!(before, fingerprint(dir.path(), &Config::default()).unwrap());
+
+        fs::write(dir.path().join("a.txt"), "foobar").unwrap();
+        assert_ne!(before, fingerprint(dir.path(), &Config::default()).unwrap());
+
+        let before = fingerprint(dir.path(), &Config::default()).unwrap();
+        fs::write(dir.path().join("b.txt"), "").unwrap();
+        assert_ne!(before, fingerprint(dir.path(), &Config::default()).unwrap());
+
+        let before = fingerprint(dir.path(), &Config::default()).unwrap();
+        fs::write(dir.path().join(".submitUser"), "").unwrap();
+        assert_eq!(before, fingerprint(dir.path(), &Config::default()).unwrap());
+    }
+}